@@ -0,0 +1,70 @@
+//
+// Copyright (c) 2025 Nathan Fiedler
+//
+use crate::stats::Summary;
+use crate::{Config, Times};
+use std::time::{Duration, Instant};
+
+/// Settings for repeating the phase benchmarks until their results are
+/// precise enough, instead of a fixed number of times.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Adaptive {
+    /// Number of runs performed first, whose results are discarded.
+    pub warmups: usize,
+    /// Half-width of the 95% confidence interval of the mean, relative to
+    /// the mean, that every phase must reach.
+    pub target: f64,
+    /// Time after which no more runs are started, including the warmups.
+    pub budget: Duration,
+    /// Largest number of runs whose results are kept.
+    pub max_runs: usize,
+}
+
+impl Default for Adaptive {
+    fn default() -> Self {
+        Self {
+            warmups: 1,
+            target: 0.01,
+            budget: Duration::from_secs(60),
+            max_runs: 1_000,
+        }
+    }
+}
+
+/// Perform the runs of the phase benchmarks by calling `run`, stopping at
+/// the first error. Without `config.adaptive` that is exactly `config.runs`
+/// runs. Otherwise it is the warmup runs, then as many as needed for the
+/// confidence interval of every phase to reach the target, unless the time
+/// budget or the maximum number of runs is reached first.
+pub fn repeat<E>(
+    config: &Config,
+    mut run: impl FnMut() -> Result<Times, E>,
+) -> Result<Vec<Times>, E> {
+    let Some(adaptive) = config.adaptive else {
+        return (0..config.runs).map(|_| run()).collect();
+    };
+    let start = Instant::now();
+    for _ in 0..adaptive.warmups {
+        run()?;
+    }
+    let mut times = vec![];
+    while times.len() < adaptive.max_runs {
+        times.push(run()?);
+        if precise(&times, adaptive.target) || start.elapsed() >= adaptive.budget {
+            break;
+        }
+    }
+    Ok(times)
+}
+
+/// Returns `true` if the relative confidence interval of every phase is
+/// within the target.
+fn precise(times: &[Times], target: f64) -> bool {
+    let phases = times[0].phases().len();
+    (0..phases).all(|index| {
+        let samples: Vec<Duration> = times.iter().map(|t| t.phases()[index].1.elapsed).collect();
+        Summary::new(&samples)
+            .relative_ci()
+            .is_some_and(|precision| precision <= target)
+    })
+}
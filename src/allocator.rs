@@ -0,0 +1,163 @@
+//
+// Copyright (c) 2025 Nathan Fiedler
+//
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::ops::{Add, Div, Sub};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static ALLOCS: AtomicU64 = AtomicU64::new(0);
+static DEALLOCS: AtomicU64 = AtomicU64::new(0);
+static REALLOCS: AtomicU64 = AtomicU64::new(0);
+static BYTES_REQUESTED: AtomicU64 = AtomicU64::new(0);
+static BYTES_MOVED: AtomicU64 = AtomicU64::new(0);
+static LIVE_BYTES: AtomicU64 = AtomicU64::new(0);
+static PEAK_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// Record that the heap grew by the given number of bytes.
+fn grow(bytes: usize) {
+    let live = LIVE_BYTES.fetch_add(bytes as u64, Ordering::Relaxed) + bytes as u64;
+    PEAK_BYTES.fetch_max(live, Ordering::Relaxed);
+}
+
+/// Record that the heap shrank by the given number of bytes.
+fn shrink(bytes: usize) {
+    LIVE_BYTES.fetch_sub(bytes as u64, Ordering::Relaxed);
+}
+
+/// Global allocator that forwards to the system allocator while counting
+/// every call made to it.
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCS.fetch_add(1, Ordering::Relaxed);
+        BYTES_REQUESTED.fetch_add(layout.size() as u64, Ordering::Relaxed);
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        ALLOCS.fetch_add(1, Ordering::Relaxed);
+        BYTES_REQUESTED.fetch_add(layout.size() as u64, Ordering::Relaxed);
+        let ptr = unsafe { System.alloc_zeroed(layout) };
+        if !ptr.is_null() {
+            grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        DEALLOCS.fetch_add(1, Ordering::Relaxed);
+        shrink(layout.size());
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        REALLOCS.fetch_add(1, Ordering::Relaxed);
+        BYTES_REQUESTED.fetch_add(new_size as u64, Ordering::Relaxed);
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() {
+            if new_size > layout.size() {
+                grow(new_size - layout.size());
+            } else {
+                shrink(layout.size() - new_size);
+            }
+            if new_ptr != ptr {
+                let moved = layout.size().min(new_size);
+                BYTES_MOVED.fetch_add(moved as u64, Ordering::Relaxed);
+            }
+        }
+        new_ptr
+    }
+}
+
+/// Totals of the allocator activity, either since the start of the program
+/// (see [`counts`]) or between two points in time (by subtraction).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Counts {
+    /// Calls to `alloc` and `alloc_zeroed`.
+    pub allocs: u64,
+    /// Calls to `dealloc`.
+    pub deallocs: u64,
+    /// Calls to `realloc`.
+    pub reallocs: u64,
+    /// Sum of the sizes given to `alloc` and the new sizes given to `realloc`.
+    pub bytes_requested: u64,
+    /// Bytes carried over by `realloc` calls that returned a different
+    /// address. Very large blocks may have been remapped by the kernel rather
+    /// than copied, so this is an upper bound on the bytes actually copied.
+    pub bytes_moved: u64,
+}
+
+/// Returns the allocator activity since the program started.
+pub fn counts() -> Counts {
+    Counts {
+        allocs: ALLOCS.load(Ordering::Relaxed),
+        deallocs: DEALLOCS.load(Ordering::Relaxed),
+        reallocs: REALLOCS.load(Ordering::Relaxed),
+        bytes_requested: BYTES_REQUESTED.load(Ordering::Relaxed),
+        bytes_moved: BYTES_MOVED.load(Ordering::Relaxed),
+    }
+}
+
+/// Returns the number of bytes currently allocated from the heap.
+pub fn live_bytes() -> u64 {
+    LIVE_BYTES.load(Ordering::Relaxed)
+}
+
+/// Returns the largest number of bytes allocated at any one time since the
+/// last call to [`reset_peak`] (or since the program started).
+pub fn peak_bytes() -> u64 {
+    PEAK_BYTES.load(Ordering::Relaxed)
+}
+
+/// Start tracking the peak heap usage over again from the current usage.
+pub fn reset_peak() {
+    PEAK_BYTES.store(LIVE_BYTES.load(Ordering::Relaxed), Ordering::Relaxed);
+}
+
+impl Add for Counts {
+    type Output = Counts;
+
+    fn add(self, other: Counts) -> Counts {
+        Counts {
+            allocs: self.allocs + other.allocs,
+            deallocs: self.deallocs + other.deallocs,
+            reallocs: self.reallocs + other.reallocs,
+            bytes_requested: self.bytes_requested + other.bytes_requested,
+            bytes_moved: self.bytes_moved + other.bytes_moved,
+        }
+    }
+}
+
+impl Sub for Counts {
+    type Output = Counts;
+
+    fn sub(self, other: Counts) -> Counts {
+        Counts {
+            allocs: self.allocs - other.allocs,
+            deallocs: self.deallocs - other.deallocs,
+            reallocs: self.reallocs - other.reallocs,
+            bytes_requested: self.bytes_requested - other.bytes_requested,
+            bytes_moved: self.bytes_moved - other.bytes_moved,
+        }
+    }
+}
+
+/// Divide every count by `n`, rounding down, to get a per-run average.
+impl Div<u64> for Counts {
+    type Output = Counts;
+
+    fn div(self, n: u64) -> Counts {
+        Counts {
+            allocs: self.allocs / n,
+            deallocs: self.deallocs / n,
+            reallocs: self.reallocs / n,
+            bytes_requested: self.bytes_requested / n,
+            bytes_moved: self.bytes_moved / n,
+        }
+    }
+}
@@ -0,0 +1,215 @@
+//
+// Copyright (c) 2025 Nathan Fiedler
+//
+use extarray::ExtensibleArray;
+use hashed_array_tree::HashedArrayTree;
+use optarray::OptimalArray as BrodnikArray;
+use segment_array::SegmentArray;
+use tzarrays::general::OptimalArray as GeneralArray;
+use tzarrays::simple::OptimalArray as SimpleArray;
+
+/// Operations common to all of the resizable arrays being measured.
+pub trait ResizableArray<T> {
+    /// Appends an element to the back of the array.
+    fn push(&mut self, value: T);
+
+    /// Removes the last element from the array and returns it, or `None` if
+    /// the array is empty.
+    fn pop(&mut self) -> Option<T>;
+
+    /// Returns the number of elements in the array.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if the array contains no elements.
+    fn is_empty(&self) -> bool;
+
+    /// Returns a reference to the element at the given index, or `None` if
+    /// the index is out of bounds.
+    fn get(&self, index: usize) -> Option<&T>;
+
+    /// Returns a mutable reference to the element at the given index, or
+    /// `None` if the index is out of bounds.
+    fn get_mut(&mut self, index: usize) -> Option<&mut T>;
+
+    /// Calls the closure on every element of the array, in order, with a
+    /// mutable reference to that element.
+    ///
+    /// Arrays that offer `iter_mut()` use it, while the default visits each
+    /// element through `get_mut()`.
+    fn for_each_mut(&mut self, mut f: impl FnMut(&mut T)) {
+        for index in 0..self.len() {
+            f(self.get_mut(index).unwrap());
+        }
+    }
+
+    /// Removes all elements from the array.
+    fn clear(&mut self);
+
+    /// Shortens the array to the first `len` elements, dropping the rest. Has
+    /// no effect if `len` is not less than the current length.
+    ///
+    /// Arrays that offer `truncate()` use it, while the default pops the
+    /// excess elements one at a time.
+    fn truncate(&mut self, len: usize) {
+        while self.len() > len {
+            self.pop();
+        }
+    }
+
+    /// Removes the element at the given index and returns it, replacing it
+    /// with the last element. Panics if the index is out of bounds.
+    fn swap_remove(&mut self, index: usize) -> T;
+
+    /// Inserts an element at the given index, shifting all elements after it
+    /// toward the back. Panics if the index is greater than the length.
+    ///
+    /// Returns the element as `Err` if the array does not offer the
+    /// operation, as the default does.
+    fn insert(&mut self, index: usize, value: T) -> Result<(), T> {
+        let _ = index;
+        Err(value)
+    }
+
+    /// Removes the element at the given index and returns it, shifting all
+    /// elements after it toward the front. Panics if the index is out of
+    /// bounds.
+    ///
+    /// Returns `None` if the array does not offer the operation, as the
+    /// default does.
+    fn remove(&mut self, index: usize) -> Option<T> {
+        let _ = index;
+        None
+    }
+
+    /// Returns an iterator over the elements of the array, in order.
+    fn iter<'a>(&'a self) -> impl Iterator<Item = &'a T>
+    where
+        T: 'a;
+}
+
+impl<T> ResizableArray<T> for Vec<T> {
+    fn push(&mut self, value: T) {
+        Vec::push(self, value)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        Vec::pop(self)
+    }
+
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+
+    fn is_empty(&self) -> bool {
+        Vec::is_empty(self)
+    }
+
+    fn get(&self, index: usize) -> Option<&T> {
+        self.as_slice().get(index)
+    }
+
+    fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.as_mut_slice().get_mut(index)
+    }
+
+    fn for_each_mut(&mut self, f: impl FnMut(&mut T)) {
+        self.as_mut_slice().iter_mut().for_each(f)
+    }
+
+    fn clear(&mut self) {
+        Vec::clear(self)
+    }
+
+    fn truncate(&mut self, len: usize) {
+        Vec::truncate(self, len)
+    }
+
+    fn swap_remove(&mut self, index: usize) -> T {
+        Vec::swap_remove(self, index)
+    }
+
+    fn insert(&mut self, index: usize, value: T) -> Result<(), T> {
+        Vec::insert(self, index, value);
+        Ok(())
+    }
+
+    fn remove(&mut self, index: usize) -> Option<T> {
+        Some(Vec::remove(self, index))
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = &'a T>
+    where
+        T: 'a,
+    {
+        self.as_slice().iter()
+    }
+}
+
+/// Implement `ResizableArray` for types whose inherent methods already have
+/// the same names and signatures as the trait. Any additional trait methods
+/// given in braces after the type name are included in its implementation.
+macro_rules! impl_resizable_array {
+    ($($array:ident $({ $($extra:item)* })?),+) => {
+        $(
+            impl<T> ResizableArray<T> for $array<T> {
+                $($($extra)*)?
+
+                fn push(&mut self, value: T) {
+                    $array::push(self, value)
+                }
+
+                fn pop(&mut self) -> Option<T> {
+                    $array::pop(self)
+                }
+
+                fn len(&self) -> usize {
+                    $array::len(self)
+                }
+
+                fn is_empty(&self) -> bool {
+                    $array::is_empty(self)
+                }
+
+                fn get(&self, index: usize) -> Option<&T> {
+                    $array::get(self, index)
+                }
+
+                fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+                    $array::get_mut(self, index)
+                }
+
+                fn clear(&mut self) {
+                    $array::clear(self)
+                }
+
+                fn swap_remove(&mut self, index: usize) -> T {
+                    $array::swap_remove(self, index)
+                }
+
+                fn iter<'a>(&'a self) -> impl Iterator<Item = &'a T>
+                where
+                    T: 'a,
+                {
+                    $array::iter(self)
+                }
+            }
+        )+
+    };
+}
+
+impl_resizable_array!(
+    SegmentArray,
+    HashedArrayTree {
+        fn for_each_mut(&mut self, f: impl FnMut(&mut T)) {
+            HashedArrayTree::iter_mut(self).for_each(f)
+        }
+
+        fn truncate(&mut self, len: usize) {
+            HashedArrayTree::truncate(self, len)
+        }
+    },
+    BrodnikArray,
+    ExtensibleArray,
+    GeneralArray,
+    SimpleArray
+);
@@ -0,0 +1,403 @@
+//
+// Copyright (c) 2025 Nathan Fiedler
+//
+use crate::report::{self, Measurement};
+use crate::stats;
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Directory, relative to the working directory, in which baselines are kept.
+const BASELINE_DIR: &str = "target/array-bench/baselines";
+
+/// Samples of one phase of one implementation read back from a baseline.
+pub struct Record {
+    pub key: String,
+    pub element: String,
+    pub mode: String,
+    /// Source of the time measurements.
+    pub timer: String,
+    pub size: usize,
+    pub phase: String,
+    pub samples: Vec<Duration>,
+}
+
+/// Outcome of comparing one phase of one implementation against a baseline.
+pub struct Comparison {
+    pub label: String,
+    pub mode: &'static str,
+    pub element: String,
+    pub size: usize,
+    pub phase: String,
+    pub baseline_mean: Duration,
+    pub current_mean: Duration,
+    /// Change in the mean time relative to the baseline, in percent.
+    pub change: f64,
+    /// Result of Welch's t-test at the 95% level, if it could be applied.
+    pub significant: Option<bool>,
+}
+
+impl Comparison {
+    /// Returns `true` if the phase became slower by more than `threshold`
+    /// percent, and the difference is statistically significant.
+    pub fn is_regression(&self, threshold: f64) -> bool {
+        self.change > threshold && self.significant == Some(true)
+    }
+}
+
+/// Path of the file that holds the named baseline.
+pub fn path(name: &str) -> PathBuf {
+    PathBuf::from(BASELINE_DIR).join(format!("{name}.csv"))
+}
+
+/// Write the measurements to the named baseline, replacing any that exists.
+pub fn save(name: &str, measurements: &[Measurement]) -> io::Result<PathBuf> {
+    let path = path(name);
+    fs::create_dir_all(BASELINE_DIR)?;
+    fs::write(&path, report::csv(measurements))?;
+    Ok(path)
+}
+
+/// Read the records of the named baseline.
+pub fn load(name: &str) -> io::Result<Vec<Record>> {
+    let path = path(name);
+    let text = fs::read_to_string(&path)?;
+    parse(&text).map_err(|msg| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: {msg}", path.display()),
+        )
+    })
+}
+
+/// Parse the records from the text of a baseline, in the CSV format
+/// written by `report::csv()`.
+fn parse(text: &str) -> Result<Vec<Record>, String> {
+    let mut lines = text.lines();
+    let header = split_csv(lines.next().unwrap_or_default());
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|h| h == name)
+            .ok_or_else(|| format!("missing column {name}"))
+    };
+    let key = column("key")?;
+    let element = column("element")?;
+    let mode = column("mode")?;
+    let timer = column("timer")?;
+    let size = column("size")?;
+    let phase = column("phase")?;
+    let samples = column("samples_ns")?;
+    let mut records = vec![];
+    for (number, line) in lines.enumerate() {
+        let fields = split_csv(line);
+        if fields.len() != header.len() {
+            return Err(format!("wrong number of fields on line {}", number + 2));
+        }
+        let parse_err = |_| format!("invalid number on line {}", number + 2);
+        let samples = fields[samples]
+            .split(';')
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse::<u64>().map(Duration::from_nanos))
+            .collect::<Result<Vec<Duration>, _>>()
+            .map_err(parse_err)?;
+        records.push(Record {
+            key: fields[key].clone(),
+            element: fields[element].clone(),
+            mode: fields[mode].clone(),
+            timer: fields[timer].clone(),
+            size: fields[size].parse().map_err(parse_err)?,
+            phase: fields[phase].clone(),
+            samples,
+        });
+    }
+    Ok(records)
+}
+
+/// Compare every phase of the measurements with the matching record of the
+/// baseline, if there is one. Records are matched by implementation key,
+/// element type, mode, timer, size, and phase, so that times measured with
+/// different clocks are never compared.
+pub fn compare(baseline: &[Record], measurements: &[Measurement]) -> Vec<Comparison> {
+    let mut comparisons = vec![];
+    for m in measurements {
+        for result in &m.phases {
+            let Some(record) = baseline.iter().find(|r| {
+                r.key == m.implementation.key
+                    && r.element == m.element.name()
+                    && r.mode == m.mode.name()
+                    && r.timer == m.timer.name()
+                    && r.size == m.size
+                    && r.phase == result.phase
+            }) else {
+                continue;
+            };
+            if record.samples.is_empty() {
+                continue;
+            }
+            let baseline_mean = stats::Summary::new(&record.samples).mean;
+            let current_mean = result.summary.mean;
+            let change = if baseline_mean.is_zero() {
+                0.0
+            } else {
+                (current_mean.as_secs_f64() / baseline_mean.as_secs_f64() - 1.0) * 100.0
+            };
+            comparisons.push(Comparison {
+                label: m.implementation.label(),
+                mode: m.mode.name(),
+                element: record.element.clone(),
+                size: m.size,
+                phase: result.phase.to_owned(),
+                baseline_mean,
+                current_mean,
+                change,
+                significant: stats::significantly_different(&record.samples, &result.samples),
+            });
+        }
+    }
+    comparisons
+}
+
+/// Render the comparisons as a table, marking regressions beyond the
+/// threshold (in percent).
+pub fn table(comparisons: &[Comparison], threshold: f64) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "{:<24} {:<8} {:<5} {:>12} {:<14} {:>14} {:>14} {:>9} {:>12}  verdict",
+        "implementation",
+        "element",
+        "mode",
+        "size",
+        "phase",
+        "baseline",
+        "current",
+        "change",
+        "significant"
+    );
+    for c in comparisons {
+        let significant = match c.significant {
+            Some(true) => "yes",
+            Some(false) => "no",
+            None => "n/a",
+        };
+        let verdict = if c.is_regression(threshold) {
+            "REGRESSION"
+        } else if c.change < -threshold && c.significant == Some(true) {
+            "improved"
+        } else {
+            "unchanged"
+        };
+        let _ = writeln!(
+            out,
+            "{:<24} {:<8} {:<5} {:>12} {:<14} {:>14} {:>14} {:>8.2}% {:>12}  {}",
+            c.label,
+            c.element,
+            c.mode,
+            c.size,
+            c.phase,
+            report::duration(c.baseline_mean),
+            report::duration(c.current_mean),
+            c.change,
+            significant,
+            verdict
+        );
+    }
+    out
+}
+
+/// Split a line of CSV into its fields, handling quoted fields.
+fn split_csv(line: &str) -> Vec<String> {
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::element::ElementKind;
+    use crate::memory::Footprint;
+    use crate::registry::{IMPLEMENTATIONS, Mode};
+    use crate::timer::Timer;
+    use crate::{Sample, Times};
+
+    const HEADER: &str = "key,element,mode,timer,size,phase,samples_ns";
+
+    fn record(mode: &str, timer: &str, size: usize, phase: &str, nanos: &[u64]) -> Record {
+        Record {
+            key: "vec".into(),
+            element: "usize".into(),
+            mode: mode.into(),
+            timer: timer.into(),
+            size,
+            phase: phase.into(),
+            samples: nanos.iter().copied().map(Duration::from_nanos).collect(),
+        }
+    }
+
+    /// Cold runs of `vec` with wall clock times, every phase of which took
+    /// the given number of nanoseconds.
+    fn measurement(nanos: &[u64]) -> Measurement {
+        let times: Vec<Times> = nanos
+            .iter()
+            .map(|&n| {
+                let sample = Sample {
+                    elapsed: Duration::from_nanos(n),
+                    ..Default::default()
+                };
+                Times::from_samples(Footprint::default(), &[sample; 8]).unwrap()
+            })
+            .collect();
+        Measurement::new(
+            &IMPLEMENTATIONS[0],
+            ElementKind::Usize,
+            Mode::Cold,
+            Timer::Wall,
+            100,
+            &times,
+        )
+    }
+
+    fn comparison(change: f64, significant: Option<bool>) -> Comparison {
+        Comparison {
+            label: "vec".into(),
+            mode: "cold",
+            element: "usize".into(),
+            size: 100,
+            phase: "create".into(),
+            baseline_mean: Duration::from_nanos(100),
+            current_mean: Duration::from_nanos(100),
+            change,
+            significant,
+        }
+    }
+
+    #[test]
+    fn test_split_csv() {
+        assert_eq!(split_csv("a,b,,c"), ["a", "b", "", "c"]);
+        assert_eq!(split_csv("\"a,b\",c"), ["a,b", "c"]);
+        assert_eq!(split_csv("\"say \"\"hi\"\"\",x"), ["say \"hi\"", "x"]);
+        assert_eq!(split_csv(""), [""]);
+    }
+
+    #[test]
+    fn test_parse() {
+        let text = format!(
+            "{HEADER},extra\nvec,usize,cold,wall,100,create,10;20;30,\"a,b\"\nvec,usize,warm,cycles,100,pop-all,,x\n"
+        );
+        let records = parse(&text).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].key, "vec");
+        assert_eq!(records[0].element, "usize");
+        assert_eq!(records[0].mode, "cold");
+        assert_eq!(records[0].timer, "wall");
+        assert_eq!(records[0].size, 100);
+        assert_eq!(records[0].phase, "create");
+        assert_eq!(
+            records[0].samples,
+            [10, 20, 30].map(Duration::from_nanos).to_vec()
+        );
+        assert_eq!(records[1].mode, "warm");
+        assert_eq!(records[1].timer, "cycles");
+        assert!(records[1].samples.is_empty());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for column in [
+            "key",
+            "element",
+            "mode",
+            "timer",
+            "size",
+            "phase",
+            "samples_ns",
+        ] {
+            let header: Vec<&str> = HEADER.split(',').filter(|c| *c != column).collect();
+            assert_eq!(
+                parse(&header.join(",")).err().unwrap(),
+                format!("missing column {column}")
+            );
+        }
+        assert_eq!(
+            parse(&format!("{HEADER}\nvec,usize,cold,wall,100,create\n"))
+                .err()
+                .unwrap(),
+            "wrong number of fields on line 2"
+        );
+        assert_eq!(
+            parse(&format!("{HEADER}\nvec,usize,cold,wall,100,create,1,2\n"))
+                .err()
+                .unwrap(),
+            "wrong number of fields on line 2"
+        );
+        assert_eq!(
+            parse(&format!("{HEADER}\nvec,usize,cold,wall,big,create,1\n"))
+                .err()
+                .unwrap(),
+            "invalid number on line 2"
+        );
+        assert_eq!(
+            parse(&format!("{HEADER}\nvec,usize,cold,wall,100,create,1;x\n"))
+                .err()
+                .unwrap(),
+            "invalid number on line 2"
+        );
+    }
+
+    #[test]
+    fn test_compare_matching() {
+        let current = [measurement(&[200, 201, 199, 200, 202])];
+        let baseline = [
+            record("cold", "wall", 100, "create", &[100, 101, 99, 100, 102]),
+            record("warm", "wall", 100, "ordered", &[100, 101, 99]),
+            record("cold", "cycles", 100, "indexed", &[100, 101, 99]),
+            record("cold", "wall", 1000, "random", &[100, 101, 99]),
+            record("cold", "wall", 100, "update", &[]),
+            Record {
+                key: "hat".into(),
+                ..record("cold", "wall", 100, "update-random", &[100])
+            },
+            Record {
+                element: "u8".into(),
+                ..record("cold", "wall", 100, "iter-mut", &[100])
+            },
+        ];
+        let comparisons = compare(&baseline, &current);
+        assert_eq!(comparisons.len(), 1);
+        let c = &comparisons[0];
+        assert_eq!(c.phase, "create");
+        assert_eq!(c.mode, "cold");
+        assert_eq!(c.baseline_mean, Duration::from_nanos(100));
+        assert_eq!(c.current_mean, Duration::from_nanos(200));
+        assert!((c.change - 100.0).abs() < 1e-9);
+        assert_eq!(c.significant, Some(true));
+        assert!(c.is_regression(5.0));
+    }
+
+    #[test]
+    fn test_is_regression() {
+        assert!(comparison(10.0, Some(true)).is_regression(5.0));
+        assert!(!comparison(10.0, Some(false)).is_regression(5.0));
+        assert!(!comparison(10.0, None).is_regression(5.0));
+        assert!(!comparison(5.0, Some(true)).is_regression(5.0));
+        assert!(!comparison(-10.0, Some(true)).is_regression(5.0));
+    }
+}
@@ -0,0 +1,391 @@
+//
+// Copyright (c) 2025 Nathan Fiedler
+//
+use crate::isolate::Isolation;
+use array_bench::adaptive::Adaptive;
+use array_bench::element::ElementKind;
+use array_bench::registry::Mode;
+use array_bench::report::Format;
+use array_bench::timer::Timer;
+use array_bench::workload::Workload;
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Usage text shown for `--help` and after argument errors.
+pub const USAGE: &str = "\
+Usage: array-bench [OPTIONS]
+       array-bench replay TRACE [OPTIONS]
+
+Commands:
+  replay TRACE    perform the operations listed in the trace file with every
+                  implementation instead of running the phase benchmarks
+
+Options:
+  --size N        number of elements to push in each run (default 100000000)
+  --runs N        number of times each benchmark is repeated (default 7)
+  --element TYPE  type of the values stored in the arrays: u8, u32, usize,
+                  u128, pod64, page4k, string, or box (default usize)
+  --mode MODE     measure a new array for every run (cold), the same array
+                  for every run (warm), or both (default both)
+  --timer TIMER   source of the time measurements: wall (wall clock time),
+                  cpu (CPU time of the thread, Linux only), or cycles (time
+                  stamp counter, x86_64 only) (default wall)
+  --perf          count page faults, context switches, CPU migrations,
+                  instructions, cache misses, and branch misses during each
+                  phase (Linux only; unavailable counters are reported as n/a)
+  --adaptive      repeat the benchmarks until the 95% confidence interval of
+                  every phase is within --target of the mean, instead of
+                  --runs times
+  --warmups N     number of runs discarded before measuring in adaptive
+                  mode (default 1)
+  --target PCT    precision to reach in adaptive mode, as a percentage of
+                  the mean (default 1)
+  --budget SECS   time after which adaptive mode stops starting new runs of
+                  an implementation (default 60)
+  --max-runs N    largest number of runs in adaptive mode (default 1000)
+  --seed N        seed for the pseudo-random access order (default 42)
+  --sweep         run every benchmark at sizes from --min-size up to --size,
+                  spaced logarithmically
+  --min-size N    smallest size in a sweep (default 1000)
+  --steps N       number of sizes per decade in a sweep (default 1)
+  --isolate MODE  run each implementation, or each run, in a new process:
+                  none, implementation, or run (default none)
+  --latency       record the latency of individual pushes instead of
+                  running the phase benchmarks
+  --workload PATTERN
+                  perform --size pushes and pops following the pattern,
+                  random-walk, sawtooth, or bursty, instead of running the
+                  phase benchmarks
+  --push-ratio P  probability that an operation (random-walk) or a burst
+                  (bursty) consists of pushes (default 0.5)
+  --period N      length of each tooth (sawtooth) or the longest burst
+                  (bursty) (default 1000)
+  --thrash        find the lengths at which each array allocates, then
+                  push and pop across them, instead of running the phase
+                  benchmarks
+  --boundaries N  number of lengths at which to push and pop (default 10)
+  --cycles N      number of pushes and pops at each length (default 10000)
+  --insert-remove insert and remove elements at the front, middle, back, and
+                  random positions of an array of --size elements, instead
+                  of running the phase benchmarks
+  --edits N       number of elements inserted or removed in each phase of
+                  --insert-remove (default 1000)
+  --batch N       number of operations timed together in latency and
+                  workload modes (default 1)
+  --only KEYS     comma-separated list of implementations to run
+  --skip KEYS     comma-separated list of implementations to exclude
+  --save-baseline NAME
+                  save the results as the named baseline
+  --compare NAME  compare the results with the named baseline, exiting with
+                  status 1 if any phase regressed beyond the threshold
+  --threshold PCT percentage slowdown counted as a regression (default 5)
+  --format FMT    output format: table, json, or csv (default table)
+  --list          print the available implementations and exit
+  -h, --help      print this help and exit";
+
+/// Settings gathered from the command line.
+#[derive(Debug)]
+pub struct Options {
+    pub size: usize,
+    pub runs: usize,
+    pub adaptive: Option<Adaptive>,
+    pub seed: u64,
+    pub element: ElementKind,
+    pub modes: Vec<Mode>,
+    pub timer: Timer,
+    pub perf: bool,
+    pub sweep: bool,
+    pub min_size: usize,
+    pub steps: usize,
+    pub isolate: Isolation,
+    /// Set when this process was started by another to measure one
+    /// implementation and report the results on standard output.
+    pub child: bool,
+    pub latency: bool,
+    pub batch: usize,
+    pub workload: Option<Workload>,
+    pub thrash: bool,
+    /// Trace file to replay, given by the `replay` command.
+    pub replay: Option<PathBuf>,
+    pub boundaries: usize,
+    pub cycles: usize,
+    pub insert_remove: bool,
+    pub edits: usize,
+    pub only: Vec<String>,
+    pub skip: Vec<String>,
+    pub format: Format,
+    pub save_baseline: Option<String>,
+    pub compare: Option<String>,
+    pub threshold: f64,
+    pub list: bool,
+    pub help: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            size: 100_000_000,
+            runs: 7,
+            adaptive: None,
+            seed: 42,
+            element: ElementKind::Usize,
+            modes: vec![Mode::Cold, Mode::Warm],
+            timer: Timer::Wall,
+            perf: false,
+            sweep: false,
+            min_size: 1_000,
+            steps: 1,
+            isolate: Isolation::None,
+            child: false,
+            latency: false,
+            batch: 1,
+            workload: None,
+            thrash: false,
+            replay: None,
+            boundaries: 10,
+            cycles: 10_000,
+            insert_remove: false,
+            edits: 1_000,
+            only: vec![],
+            skip: vec![],
+            format: Format::Table,
+            save_baseline: None,
+            compare: None,
+            threshold: 5.0,
+            list: false,
+            help: false,
+        }
+    }
+}
+
+/// Problem encountered while parsing the command line.
+#[derive(Debug)]
+pub struct ArgError(String);
+
+impl fmt::Display for ArgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for ArgError {}
+
+/// Parse the given arguments, which should not include the program name.
+///
+/// Options may be written either as `--name value` or `--name=value`.
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Options, ArgError> {
+    let mut options = Options::default();
+    let mut workload = Workload::default();
+    let mut adaptive = Adaptive::default();
+    let mut args = args.into_iter().peekable();
+    if args.next_if(|arg| arg == "replay").is_some() {
+        let path = args
+            .next()
+            .filter(|path| !path.starts_with('-'))
+            .ok_or_else(|| ArgError("missing trace file for replay".into()))?;
+        options.replay = Some(PathBuf::from(path));
+    }
+    while let Some(arg) = args.next() {
+        let (name, inline) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => {
+                (name.to_owned(), Some(value.to_owned()))
+            }
+            _ => (arg, None),
+        };
+        let mut value = || {
+            inline
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| ArgError(format!("missing value for {name}")))
+        };
+        match name.as_str() {
+            "--size" => options.size = parse_count(&name, &value()?)?,
+            "--runs" => options.runs = parse_count(&name, &value()?)?,
+            "--adaptive" => options.adaptive = Some(adaptive),
+            "--warmups" => adaptive.warmups = parse_count(&name, &value()?)?,
+            "--target" => {
+                let text = value()?;
+                adaptive.target = text
+                    .parse()
+                    .ok()
+                    .filter(|t: &f64| t.is_finite() && *t > 0.0)
+                    .map(|t| t / 100.0)
+                    .ok_or_else(|| ArgError(format!("invalid value for {name}: {text}")))?;
+            }
+            "--budget" => {
+                let text = value()?;
+                adaptive.budget = text
+                    .parse()
+                    .ok()
+                    .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+                    .filter(|budget| !budget.is_zero())
+                    .ok_or_else(|| ArgError(format!("invalid value for {name}: {text}")))?;
+            }
+            "--max-runs" => adaptive.max_runs = parse_count(&name, &value()?)?,
+            "--seed" => options.seed = parse_count(&name, &value()?)?,
+            "--element" => options.element = value()?.parse().map_err(ArgError)?,
+            "--mode" => {
+                options.modes = match value()?.as_str() {
+                    "both" => vec![Mode::Cold, Mode::Warm],
+                    other => vec![other.parse().map_err(ArgError)?],
+                }
+            }
+            "--timer" => options.timer = value()?.parse().map_err(ArgError)?,
+            "--perf" => options.perf = true,
+            "--sweep" => options.sweep = true,
+            "--min-size" => options.min_size = parse_count(&name, &value()?)?,
+            "--steps" => options.steps = parse_count(&name, &value()?)?,
+            "--isolate" => options.isolate = value()?.parse().map_err(ArgError)?,
+            "--child" => options.child = true,
+            "--latency" => options.latency = true,
+            "--batch" => options.batch = parse_count(&name, &value()?)?,
+            "--workload" => {
+                workload.pattern = value()?.parse().map_err(ArgError)?;
+                options.workload = Some(workload);
+            }
+            "--push-ratio" => {
+                let text = value()?;
+                workload.push_ratio = text
+                    .parse()
+                    .ok()
+                    .filter(|p: &f64| (0.0..=1.0).contains(p))
+                    .ok_or_else(|| ArgError(format!("invalid value for {name}: {text}")))?;
+            }
+            "--period" => workload.period = parse_count(&name, &value()?)?,
+            "--thrash" => options.thrash = true,
+            "--boundaries" => options.boundaries = parse_count(&name, &value()?)?,
+            "--cycles" => options.cycles = parse_count(&name, &value()?)?,
+            "--insert-remove" => options.insert_remove = true,
+            "--edits" => options.edits = parse_count(&name, &value()?)?,
+            "--only" => options.only.extend(parse_list(&value()?)),
+            "--skip" => options.skip.extend(parse_list(&value()?)),
+            "--format" => options.format = value()?.parse().map_err(ArgError)?,
+            "--save-baseline" => options.save_baseline = Some(parse_name(&name, &value()?)?),
+            "--compare" => options.compare = Some(parse_name(&name, &value()?)?),
+            "--threshold" => {
+                let text = value()?;
+                options.threshold = text
+                    .parse()
+                    .ok()
+                    .filter(|t: &f64| t.is_finite() && *t >= 0.0)
+                    .ok_or_else(|| ArgError(format!("invalid value for {name}: {text}")))?;
+            }
+            "--list" => options.list = true,
+            "-h" | "--help" => options.help = true,
+            _ => return Err(ArgError(format!("unrecognized argument: {name}"))),
+        }
+    }
+    if let Some(selected) = &mut options.workload {
+        // settings may be given before or after the pattern
+        *selected = workload;
+        if workload.period == 0 {
+            return Err(ArgError("--period must be at least 1".into()));
+        }
+    }
+    if let Some(selected) = &mut options.adaptive {
+        // settings may be given before or after --adaptive
+        *selected = adaptive;
+        if adaptive.max_runs == 0 {
+            return Err(ArgError("--max-runs must be at least 1".into()));
+        }
+    }
+    if options.runs == 0 {
+        return Err(ArgError("--runs must be at least 1".into()));
+    }
+    // each of these replaces the phase benchmarks with something else
+    let special: Vec<&str> = [
+        ("--latency", options.latency),
+        ("--workload", options.workload.is_some()),
+        ("--thrash", options.thrash),
+        ("--insert-remove", options.insert_remove),
+        ("replay", options.replay.is_some()),
+    ]
+    .into_iter()
+    .filter_map(|(name, set)| set.then_some(name))
+    .collect();
+    if let [first, second, ..] = special[..] {
+        return Err(ArgError(format!(
+            "{first} cannot be combined with {second}"
+        )));
+    }
+    if let Some(special) = special.first() {
+        if options.sweep {
+            return Err(ArgError(format!(
+                "--sweep cannot be combined with {special}"
+            )));
+        }
+        if options.save_baseline.is_some() || options.compare.is_some() {
+            return Err(ArgError(format!("baselines cannot be used with {special}")));
+        }
+        if options.isolate != Isolation::None {
+            return Err(ArgError(format!("--isolate cannot be used with {special}")));
+        }
+        if options.adaptive.is_some() {
+            return Err(ArgError(format!(
+                "--adaptive cannot be combined with {special}"
+            )));
+        }
+    }
+    if options.sweep {
+        if options.steps == 0 {
+            return Err(ArgError("--steps must be at least 1".into()));
+        }
+        if options.min_size == 0 || options.min_size > options.size {
+            return Err(ArgError(
+                "--min-size must be at least 1 and no larger than --size".into(),
+            ));
+        }
+    }
+    if options.boundaries == 0 || options.cycles == 0 {
+        return Err(ArgError(
+            "--boundaries and --cycles must be at least 1".into(),
+        ));
+    }
+    if options.insert_remove && options.edits > options.size {
+        return Err(ArgError("--edits must be no larger than --size".into()));
+    }
+    if options.child && (options.only.len() != 1 || options.modes.len() != 1) {
+        return Err(ArgError(
+            "--child requires exactly one --only key and one --mode".into(),
+        ));
+    }
+    if options.batch == 0 {
+        return Err(ArgError("--batch must be at least 1".into()));
+    }
+    Ok(options)
+}
+
+/// Parse a non-negative integer, permitting `_` as a digit separator.
+fn parse_count<N: FromStr>(name: &str, value: &str) -> Result<N, ArgError> {
+    value
+        .replace('_', "")
+        .parse()
+        .map_err(|_| ArgError(format!("invalid value for {name}: {value}")))
+}
+
+/// Accept a baseline name, which becomes part of a file name.
+fn parse_name(name: &str, value: &str) -> Result<String, ArgError> {
+    let valid = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        && !value.starts_with('.');
+    if valid {
+        Ok(value.to_owned())
+    } else {
+        Err(ArgError(format!(
+            "invalid value for {name}: {value} (use letters, digits, '-', '_', and '.')"
+        )))
+    }
+}
+
+/// Split a comma-separated list, ignoring empty entries.
+fn parse_list(value: &str) -> impl Iterator<Item = String> + '_ {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_owned)
+}
@@ -0,0 +1,247 @@
+//
+// Copyright (c) 2025 Nathan Fiedler
+//
+
+//! Differential testing of every array implementation against `Vec`.
+//!
+//! Each test drives an implementation with seeded pseudo-random sequences of
+//! operations, applying the same operations to a `Vec` and checking after
+//! every step that the two agree. When they do not, the sequence is shrunk to
+//! a minimal one that still fails, which is then reported.
+
+use crate::arrays::ResizableArray;
+use crate::rng::Rng;
+use extarray::ExtensibleArray;
+use hashed_array_tree::HashedArrayTree;
+use optarray::OptimalArray as BrodnikArray;
+use segment_array::SegmentArray;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use tzarrays::general::OptimalArray as GeneralArray;
+use tzarrays::simple::OptimalArray as SimpleArray;
+
+/// A single operation applied to both the array and the oracle.
+#[derive(Clone, Copy, Debug)]
+enum Op {
+    Push(usize),
+    Pop,
+    Get(usize),
+    Set(usize, usize),
+    Iterate,
+    Truncate(usize),
+    Clear,
+}
+
+/// Where and how an array disagreed with the oracle.
+struct Failure {
+    ops: Vec<Op>,
+    step: usize,
+    message: String,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "step {} of {}: {}",
+            self.step,
+            self.ops.len(),
+            self.message
+        )?;
+        for (step, op) in self.ops.iter().enumerate() {
+            writeln!(f, "  {step:>4}: {op:?}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Generate `count` operations, favoring pushes so that the arrays grow
+/// large enough to cross several of their internal boundaries. Indices are
+/// occasionally out of bounds, to check that both sides reject them.
+fn generate(seed: u64, count: usize) -> Vec<Op> {
+    let mut rng = Rng::new(seed);
+    let mut len = 0;
+    let mut ops = Vec::with_capacity(count);
+    for _ in 0..count {
+        let index = rng.below(len + len / 8 + 2);
+        let op = match rng.below(100) {
+            0..45 => Op::Push(rng.next_u64() as usize),
+            45..60 => Op::Pop,
+            60..75 => Op::Get(index),
+            75..90 => Op::Set(index, rng.next_u64() as usize),
+            90..95 => Op::Iterate,
+            95..99 => Op::Truncate(rng.below(len + 1)),
+            _ => Op::Clear,
+        };
+        len = match op {
+            Op::Push(_) => len + 1,
+            Op::Pop => len.saturating_sub(1),
+            Op::Truncate(n) => n.min(len),
+            Op::Clear => 0,
+            _ => len,
+        };
+        ops.push(op);
+    }
+    ops
+}
+
+/// Apply the operations to a new array and to a `Vec`, returning the step at
+/// which they first disagreed (or the array panicked) and why.
+fn run<A: ResizableArray<usize>>(new: fn() -> A, ops: &[Op]) -> Result<(), (usize, String)> {
+    let mut step = 0;
+    let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut array = new();
+        let mut oracle: Vec<usize> = Vec::new();
+        for (index, op) in ops.iter().enumerate() {
+            step = index;
+            match *op {
+                Op::Push(value) => {
+                    array.push(value);
+                    oracle.push(value);
+                }
+                Op::Pop => {
+                    let (actual, expected) = (array.pop(), oracle.pop());
+                    if actual != expected {
+                        return Err(format!("pop returned {actual:?}, expected {expected:?}"));
+                    }
+                }
+                Op::Get(i) => {
+                    let (actual, expected) = (array.get(i), oracle.get(i));
+                    if actual != expected {
+                        return Err(format!(
+                            "get({i}) returned {actual:?}, expected {expected:?}"
+                        ));
+                    }
+                }
+                Op::Set(i, value) => {
+                    let actual = array.get_mut(i).map(|slot| *slot = value);
+                    let expected = oracle.get_mut(i).map(|slot| *slot = value);
+                    if actual.is_some() != expected.is_some() {
+                        return Err(format!(
+                            "get_mut({i}) returned {actual:?}, expected {expected:?}"
+                        ));
+                    }
+                }
+                Op::Iterate => {
+                    if !array.iter().eq(oracle.iter()) {
+                        return Err("iterator yielded different elements".into());
+                    }
+                }
+                Op::Truncate(n) => {
+                    array.truncate(n);
+                    oracle.truncate(n);
+                }
+                Op::Clear => {
+                    array.clear();
+                    oracle.clear();
+                }
+            }
+            if array.len() != oracle.len() || array.is_empty() != oracle.is_empty() {
+                return Err(format!(
+                    "length is {}, expected {}",
+                    array.len(),
+                    oracle.len()
+                ));
+            }
+        }
+        if !array.iter().eq(oracle.iter()) {
+            return Err("final contents differ".into());
+        }
+        Ok(())
+    }));
+    match outcome {
+        Ok(result) => result.map_err(|message| (step, message)),
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_default();
+            Err((step, format!("panicked: {message}")))
+        }
+    }
+}
+
+/// Remove ever smaller chunks of the failing sequence for as long as what is
+/// left still fails, returning the minimal failing sequence.
+fn shrink<A: ResizableArray<usize>>(new: fn() -> A, mut ops: Vec<Op>) -> Failure {
+    // everything after the failing step is irrelevant
+    if let Err((step, _)) = run(new, &ops) {
+        ops.truncate(step + 1);
+    }
+    let mut chunk = ops.len() / 2;
+    while chunk > 0 {
+        let mut start = 0;
+        while start < ops.len() {
+            let mut candidate = ops.clone();
+            candidate.drain(start..(start + chunk).min(ops.len()));
+            if run(new, &candidate).is_err() {
+                ops = candidate;
+            } else {
+                start += chunk;
+            }
+        }
+        chunk /= 2;
+    }
+    let (step, message) = run(new, &ops).expect_err("shrunk sequence should still fail");
+    Failure { ops, step, message }
+}
+
+/// Check the array against the oracle with many short sequences and a few
+/// long ones, panicking with a minimal failing sequence if any disagree.
+fn check<A: ResizableArray<usize>>(new: fn() -> A) {
+    let short = (0..200).map(|seed| (seed, 1_000));
+    let long = (1_000..1_004).map(|seed| (seed, 50_000));
+    for (seed, count) in short.chain(long) {
+        let ops = generate(seed, count);
+        if run(new, &ops).is_err() {
+            panic!(
+                "seed {seed} failed, minimal sequence at {}",
+                shrink(new, ops)
+            );
+        }
+    }
+}
+
+#[test]
+fn test_vec() {
+    check(Vec::new);
+}
+
+#[test]
+fn test_segment_array() {
+    check(SegmentArray::new);
+}
+
+#[test]
+fn test_hashed_array_tree() {
+    check(HashedArrayTree::new);
+}
+
+#[test]
+#[ignore = "optarray 1.0.3 panics on push after push, pop, clear"]
+fn test_brodnik_array() {
+    check(BrodnikArray::new);
+}
+
+#[test]
+#[ignore = "extarray 1.1.2 panics on push after push, pop, clear"]
+fn test_extensible_array() {
+    check(ExtensibleArray::new);
+}
+
+#[test]
+fn test_general_array_r3() {
+    check(GeneralArray::new);
+}
+
+#[test]
+fn test_general_array_r4() {
+    check(|| GeneralArray::with_r(4));
+}
+
+#[test]
+#[ignore = "tzarrays 1.0.1 simple array loses a push that follows push x5, pop x2"]
+fn test_simple_array() {
+    check(SimpleArray::new);
+}
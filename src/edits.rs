@@ -0,0 +1,145 @@
+//
+// Copyright (c) 2025 Nathan Fiedler
+//
+use crate::Config;
+use crate::arrays::ResizableArray;
+use crate::element::Element;
+use crate::rng::Rng;
+use std::hint::black_box;
+use std::time::Duration;
+
+/// Where in the array the elements are inserted or removed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Position {
+    Front,
+    Middle,
+    Back,
+    Random,
+}
+
+/// What is done at each position.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Edit {
+    Insert,
+    Remove,
+    SwapRemove,
+}
+
+/// The phases of the benchmark, in the order they are run. Each removal
+/// phase undoes the insertion phase before it, so every phase starts with
+/// the same number of elements.
+const PHASES: [(&str, Edit, Position); 9] = [
+    ("insert-front", Edit::Insert, Position::Front),
+    ("remove-front", Edit::Remove, Position::Front),
+    ("insert-middle", Edit::Insert, Position::Middle),
+    ("remove-middle", Edit::Remove, Position::Middle),
+    ("insert-back", Edit::Insert, Position::Back),
+    ("remove-back", Edit::Remove, Position::Back),
+    ("insert-random", Edit::Insert, Position::Random),
+    ("remove-random", Edit::Remove, Position::Random),
+    ("swap-remove", Edit::SwapRemove, Position::Random),
+];
+
+/// Results of one phase of the benchmark.
+pub struct EditPhase {
+    pub phase: &'static str,
+    /// Time taken by all of the edits, for each run, or `None` if the array
+    /// does not offer the operation.
+    pub elapsed: Option<Vec<Duration>>,
+}
+
+/// Results of the insert and remove benchmark for one implementation.
+pub struct Edits {
+    /// Number of elements inserted or removed in each phase.
+    pub edits: usize,
+    pub phases: Vec<EditPhase>,
+}
+
+/// Returns the indices at which to perform `count` edits on an array that
+/// starts with `len` elements, growing by one after each insertion or
+/// shrinking by one after each removal.
+fn indices(edit: Edit, position: Position, len: usize, count: usize, rng: &mut Rng) -> Vec<usize> {
+    (0..count)
+        .map(|k| {
+            // number of valid positions for this edit
+            let bound = match edit {
+                Edit::Insert => len + k + 1,
+                Edit::Remove | Edit::SwapRemove => len - k,
+            };
+            match position {
+                Position::Front => 0,
+                Position::Middle => (bound - 1) / 2,
+                Position::Back => bound - 1,
+                Position::Random => rng.below(bound),
+            }
+        })
+        .collect()
+}
+
+/// Fill a new array with `config.size` values, then insert and remove
+/// `config.edits` values at the front, middle, back, and pseudo-random
+/// positions, and finally swap-remove `config.edits` values at random
+/// positions, `config.runs` times. A phase stops at the first insertion or
+/// removal that the array does not offer, and is reported as unsupported.
+pub fn measure<E: Element, A: ResizableArray<E>>(new: fn() -> A, config: &Config) -> Edits {
+    let mut elapsed: Vec<Vec<Duration>> = vec![vec![]; PHASES.len()];
+    for _ in 0..config.runs {
+        let mut coll = new();
+        for value in 0..config.size {
+            coll.push(E::new(value));
+        }
+        let mut expected = config.size;
+        let mut rng = Rng::new(config.seed);
+        for (index, (_, edit, position)) in PHASES.into_iter().enumerate() {
+            let len = coll.len();
+            let at = indices(edit, position, len, config.edits, &mut rng);
+            let mut supported = true;
+            let start = config.timer.start();
+            match edit {
+                Edit::Insert => {
+                    for (k, &i) in at.iter().enumerate() {
+                        if coll.insert(i, E::new(len + k)).is_err() {
+                            supported = false;
+                            break;
+                        }
+                    }
+                }
+                Edit::Remove => {
+                    for &i in &at {
+                        let Some(value) = coll.remove(i) else {
+                            supported = false;
+                            break;
+                        };
+                        black_box(value);
+                    }
+                }
+                Edit::SwapRemove => {
+                    for &i in &at {
+                        black_box(coll.swap_remove(i));
+                    }
+                }
+            }
+            let time = start.elapsed();
+            if supported {
+                elapsed[index].push(time);
+                match edit {
+                    Edit::Insert => expected += config.edits,
+                    Edit::Remove | Edit::SwapRemove => expected -= config.edits,
+                }
+            }
+        }
+        assert_eq!(coll.len(), expected);
+    }
+    let phases = PHASES
+        .iter()
+        .zip(elapsed)
+        .map(|((phase, _, _), elapsed)| EditPhase {
+            phase,
+            elapsed: (!elapsed.is_empty()).then_some(elapsed),
+        })
+        .collect();
+    Edits {
+        edits: config.edits,
+        phases,
+    }
+}
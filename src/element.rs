@@ -0,0 +1,350 @@
+//
+// Copyright (c) 2025 Nathan Fiedler
+//
+use std::fmt::Write;
+use std::str::FromStr;
+
+/// A type of value stored in the arrays being measured.
+///
+/// Every element is built from a numeric key (normally its index) so that
+/// the benchmarks can check they read back what they wrote. Keys that do not
+/// fit in the element are truncated to the bits given by `KEY_MASK`.
+pub trait Element: Sized {
+    /// Bits of the key that the element can represent.
+    const KEY_MASK: usize = usize::MAX;
+
+    /// Bytes of the element read by [`Element::load`], not counting any
+    /// memory that it owns on the heap.
+    const KEY_BYTES: usize = size_of::<Self>();
+
+    /// Bytes of the element written by [`Element::increment`], not counting
+    /// any memory that it owns on the heap.
+    const SET_BYTES: usize = size_of::<Self>();
+
+    /// Construct an element that represents the given key.
+    fn new(key: usize) -> Self;
+
+    /// Returns the key that the element represents.
+    fn key(&self) -> usize;
+
+    /// Change the element, in place, to represent the given key.
+    fn set(&mut self, key: usize);
+
+    /// Load the part of the element that holds the key, without decoding
+    /// it, for the timed loops that only read the element.
+    fn load(&self) -> usize {
+        self.key()
+    }
+
+    /// Change the element, in place, to represent its key plus one.
+    fn increment(&mut self) {
+        self.set(self.key().wrapping_add(1));
+    }
+
+    /// Change the element, in place, to represent its key minus one.
+    fn decrement(&mut self) {
+        self.set(self.key().wrapping_sub(1));
+    }
+
+    /// Returns `true` if the element represents the given key.
+    fn matches(&self, key: usize) -> bool {
+        self.key() == key & Self::KEY_MASK
+    }
+}
+
+macro_rules! impl_element_for_integer {
+    ($($int:ty),+) => {
+        $(
+            impl Element for $int {
+                const KEY_MASK: usize = <$int>::MAX as usize;
+
+                fn new(key: usize) -> Self {
+                    key as $int
+                }
+
+                fn key(&self) -> usize {
+                    *self as usize
+                }
+
+                fn set(&mut self, key: usize) {
+                    *self = key as $int;
+                }
+            }
+        )+
+    };
+}
+
+impl_element_for_integer!(u8, u32, usize, u128);
+
+/// A 64-byte plain-old-data structure, every word of which holds the key.
+#[derive(Clone, Copy, Debug)]
+pub struct Pod64 {
+    words: [u64; 8],
+}
+
+impl Element for Pod64 {
+    const KEY_BYTES: usize = 8;
+
+    fn new(key: usize) -> Self {
+        Self {
+            words: [key as u64; 8],
+        }
+    }
+
+    fn key(&self) -> usize {
+        self.words[0] as usize
+    }
+
+    fn set(&mut self, key: usize) {
+        self.words = [key as u64; 8];
+    }
+}
+
+/// A 4 KiB structure, as large as a typical memory page, whose first and
+/// last words hold the key.
+#[derive(Clone, Copy, Debug)]
+pub struct Page4K {
+    words: [u64; 512],
+}
+
+impl Element for Page4K {
+    const KEY_BYTES: usize = 8;
+    const SET_BYTES: usize = 16;
+
+    fn new(key: usize) -> Self {
+        let mut words = [0; 512];
+        words[0] = key as u64;
+        words[511] = key as u64;
+        Self { words }
+    }
+
+    fn key(&self) -> usize {
+        self.words[0] as usize
+    }
+
+    fn set(&mut self, key: usize) {
+        self.words[0] = key as u64;
+        self.words[511] = key as u64;
+    }
+}
+
+/// The key written out in decimal; updates reuse the existing buffer, loads
+/// read only the length, and increments and decrements change the digits
+/// without parsing them.
+impl Element for String {
+    const KEY_BYTES: usize = size_of::<usize>();
+
+    fn new(key: usize) -> Self {
+        key.to_string()
+    }
+
+    fn key(&self) -> usize {
+        self.parse().unwrap()
+    }
+
+    fn set(&mut self, key: usize) {
+        self.clear();
+        let _ = write!(self, "{key}");
+    }
+
+    fn load(&self) -> usize {
+        self.len()
+    }
+
+    fn increment(&mut self) {
+        let mut digits = std::mem::take(self).into_bytes();
+        // add one to the last digit that is not a nine, and make the digits
+        // after it zeros
+        let start = match digits.iter().rposition(|&digit| digit != b'9') {
+            Some(index) => {
+                digits[index] += 1;
+                index + 1
+            }
+            None => 0,
+        };
+        digits[start..].fill(b'0');
+        if start == 0 {
+            digits.insert(0, b'1');
+        }
+        *self = String::from_utf8(digits).unwrap();
+    }
+
+    fn decrement(&mut self) {
+        let mut digits = std::mem::take(self).into_bytes();
+        // subtract one from the last digit that is not a zero, and make the
+        // digits after it nines, wrapping around below zero like `usize`
+        let Some(index) = digits.iter().rposition(|&digit| digit != b'0') else {
+            self.set(usize::MAX);
+            return;
+        };
+        digits[index] -= 1;
+        digits[index + 1..].fill(b'9');
+        if digits.len() > 1 && digits[0] == b'0' {
+            digits.remove(0);
+        }
+        *self = String::from_utf8(digits).unwrap();
+    }
+}
+
+/// The key in a separate heap allocation; updates modify it in place.
+impl Element for Box<u64> {
+    fn new(key: usize) -> Self {
+        Box::new(key as u64)
+    }
+
+    fn key(&self) -> usize {
+        **self as usize
+    }
+
+    fn set(&mut self, key: usize) {
+        **self = key as u64;
+    }
+}
+
+/// The built-in element types that can be selected on the command line.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ElementKind {
+    U8,
+    U32,
+    #[default]
+    Usize,
+    U128,
+    Pod64,
+    Page4K,
+    String,
+    BoxU64,
+}
+
+impl ElementKind {
+    /// Every element kind, in the order shown to the user.
+    pub const ALL: [ElementKind; 8] = [
+        ElementKind::U8,
+        ElementKind::U32,
+        ElementKind::Usize,
+        ElementKind::U128,
+        ElementKind::Pod64,
+        ElementKind::Page4K,
+        ElementKind::String,
+        ElementKind::BoxU64,
+    ];
+
+    /// Name used to select the element kind and to identify it in results.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ElementKind::U8 => "u8",
+            ElementKind::U32 => "u32",
+            ElementKind::Usize => "usize",
+            ElementKind::U128 => "u128",
+            ElementKind::Pod64 => "pod64",
+            ElementKind::Page4K => "page4k",
+            ElementKind::String => "string",
+            ElementKind::BoxU64 => "box",
+        }
+    }
+
+    /// Size in bytes of the element itself, not counting any memory that it
+    /// owns on the heap.
+    pub fn size(&self) -> usize {
+        crate::with_element!(*self, E => size_of::<E>())
+    }
+
+    /// Bytes of the element read by [`Element::load`].
+    pub fn key_bytes(&self) -> usize {
+        crate::with_element!(*self, E => E::KEY_BYTES)
+    }
+
+    /// Bytes of the element written by [`Element::increment`].
+    pub fn set_bytes(&self) -> usize {
+        crate::with_element!(*self, E => E::SET_BYTES)
+    }
+
+    /// Returns `true` if every element owns a separate heap allocation.
+    pub fn owns_heap(&self) -> bool {
+        matches!(self, ElementKind::String | ElementKind::BoxU64)
+    }
+}
+
+impl FromStr for ElementKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ElementKind::ALL
+            .into_iter()
+            .find(|kind| kind.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<&str> = ElementKind::ALL.iter().map(|k| k.name()).collect();
+                format!(
+                    "unknown element: {s} (expected one of {})",
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// Evaluate the expression with the type alias `E` bound to the element
+/// type that corresponds to the given `ElementKind`.
+#[macro_export]
+macro_rules! with_element {
+    ($kind:expr, $E:ident => $body:expr) => {
+        match $kind {
+            $crate::element::ElementKind::U8 => {
+                type $E = u8;
+                $body
+            }
+            $crate::element::ElementKind::U32 => {
+                type $E = u32;
+                $body
+            }
+            $crate::element::ElementKind::Usize => {
+                type $E = usize;
+                $body
+            }
+            $crate::element::ElementKind::U128 => {
+                type $E = u128;
+                $body
+            }
+            $crate::element::ElementKind::Pod64 => {
+                type $E = $crate::element::Pod64;
+                $body
+            }
+            $crate::element::ElementKind::Page4K => {
+                type $E = $crate::element::Page4K;
+                $body
+            }
+            $crate::element::ElementKind::String => {
+                type $E = ::std::string::String;
+                $body
+            }
+            $crate::element::ElementKind::BoxU64 => {
+                type $E = ::std::boxed::Box<u64>;
+                $body
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_string_increment() {
+        for key in [0, 1, 8, 9, 10, 99, 109, 199, 999] {
+            let mut value = <String as Element>::new(key);
+            value.increment();
+            assert_eq!(value, (key + 1).to_string());
+        }
+    }
+
+    #[test]
+    fn test_string_decrement() {
+        for key in [1, 2, 9, 10, 11, 100, 110, 1000] {
+            let mut value = <String as Element>::new(key);
+            value.decrement();
+            assert_eq!(value, (key - 1).to_string());
+        }
+        let mut value = <String as Element>::new(0);
+        value.decrement();
+        assert_eq!(value, usize::MAX.to_string());
+    }
+}
@@ -0,0 +1,186 @@
+//
+// Copyright (c) 2025 Nathan Fiedler
+//
+
+/// Number of bits of precision kept for each recorded value. Values below
+/// `2^SUB_BUCKET_BITS` are recorded exactly, and larger values are recorded
+/// with a relative error of at most `1 / 2^(SUB_BUCKET_BITS - 1)`, or about
+/// 1.6% for 7 bits.
+const SUB_BUCKET_BITS: u32 = 7;
+const SUB_BUCKETS: usize = 1 << SUB_BUCKET_BITS;
+const HALF_BUCKETS: usize = SUB_BUCKETS / 2;
+const BUCKETS: usize = SUB_BUCKETS + (64 - SUB_BUCKET_BITS as usize) * HALF_BUCKETS;
+
+/// Histogram of `u64` values with logarithmically sized buckets, in the
+/// manner of HdrHistogram: every power of two is divided into the same
+/// number of linear sub-buckets, so the relative precision is constant over
+/// the entire range of values while the memory used is fixed.
+#[derive(Clone)]
+pub struct Histogram {
+    counts: Vec<u64>,
+    total: u64,
+    min: u64,
+    max: u64,
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Histogram {
+    pub fn new() -> Self {
+        Self {
+            counts: vec![0; BUCKETS],
+            total: 0,
+            min: u64::MAX,
+            max: 0,
+        }
+    }
+
+    /// Add a single value to the histogram.
+    pub fn record(&mut self, value: u64) {
+        self.counts[bucket_index(value)] += 1;
+        self.total += 1;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+
+    /// Number of values recorded.
+    pub fn count(&self) -> u64 {
+        self.total
+    }
+
+    /// Smallest value recorded, exactly, or zero if the histogram is empty.
+    pub fn min(&self) -> u64 {
+        if self.total == 0 { 0 } else { self.min }
+    }
+
+    /// Largest value recorded, exactly.
+    pub fn max(&self) -> u64 {
+        self.max
+    }
+
+    /// Returns the smallest value such that the given percentage (0 to 100)
+    /// of the recorded values are less than or equal to it, to within the
+    /// precision of the buckets. Returns zero if the histogram is empty.
+    pub fn percentile(&self, percent: f64) -> u64 {
+        if self.total == 0 {
+            return 0;
+        }
+        let rank = ((percent / 100.0) * self.total as f64).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for (index, count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return bucket_upper(index).min(self.max);
+            }
+        }
+        self.max
+    }
+}
+
+/// Returns the bucket that holds the given value.
+fn bucket_index(value: u64) -> usize {
+    if value < SUB_BUCKETS as u64 {
+        return value as usize;
+    }
+    // shift the value so only its top SUB_BUCKET_BITS bits remain, leaving a
+    // mantissa in [HALF_BUCKETS, SUB_BUCKETS)
+    let shift = (63 - value.leading_zeros()) - (SUB_BUCKET_BITS - 1);
+    let mantissa = (value >> shift) as usize;
+    SUB_BUCKETS + (shift as usize - 1) * HALF_BUCKETS + (mantissa - HALF_BUCKETS)
+}
+
+/// Returns the largest value that falls into the given bucket.
+fn bucket_upper(index: usize) -> u64 {
+    if index < SUB_BUCKETS {
+        return index as u64;
+    }
+    let offset = index - SUB_BUCKETS;
+    let shift = (offset / HALF_BUCKETS + 1) as u32;
+    let mantissa = (offset % HALF_BUCKETS + HALF_BUCKETS) as u64;
+    ((mantissa + 1) << shift).wrapping_sub(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small_values_are_exact() {
+        for value in 0..SUB_BUCKETS as u64 {
+            let index = bucket_index(value);
+            assert_eq!(index, value as usize);
+            assert_eq!(bucket_upper(index), value);
+        }
+    }
+
+    #[test]
+    fn test_bucket_bounds() {
+        // every value falls in a bucket whose upper bound is at least the
+        // value and within the relative precision of it
+        let mut value = 1u64;
+        let mut previous = 0;
+        while value < u64::MAX / 3 {
+            for v in [value, value + 1, value * 2 - 1] {
+                let index = bucket_index(v);
+                assert!(index < BUCKETS, "{v}");
+                let upper = bucket_upper(index);
+                assert!(upper >= v, "{v} above bucket {index} ending at {upper}");
+                assert!(
+                    (upper - v) as f64 <= v as f64 / HALF_BUCKETS as f64,
+                    "{v} in bucket {index} ending at {upper}"
+                );
+            }
+            let index = bucket_index(value);
+            assert!(index >= previous, "{value}");
+            previous = index;
+            value = value * 3 / 2 + 1;
+        }
+    }
+
+    #[test]
+    fn test_bucket_boundaries() {
+        // the value after the end of a bucket starts the next one
+        for index in 0..BUCKETS - 1 {
+            let upper = bucket_upper(index);
+            assert_eq!(bucket_index(upper), index);
+            assert_eq!(bucket_index(upper + 1), index + 1);
+        }
+        assert_eq!(bucket_index(u64::MAX), BUCKETS - 1);
+        assert_eq!(bucket_upper(BUCKETS - 1), u64::MAX);
+    }
+
+    #[test]
+    fn test_percentile() {
+        let mut histogram = Histogram::new();
+        assert_eq!(histogram.percentile(50.0), 0);
+        assert_eq!(histogram.min(), 0);
+        for value in 1..=100 {
+            histogram.record(value);
+        }
+        assert_eq!(histogram.count(), 100);
+        assert_eq!(histogram.min(), 1);
+        assert_eq!(histogram.max(), 100);
+        assert_eq!(histogram.percentile(0.0), 1);
+        assert_eq!(histogram.percentile(50.0), 50);
+        assert_eq!(histogram.percentile(99.0), 99);
+        assert_eq!(histogram.percentile(100.0), 100);
+    }
+
+    #[test]
+    fn test_percentile_large_values() {
+        let mut histogram = Histogram::new();
+        for _ in 0..99 {
+            histogram.record(1_000);
+        }
+        histogram.record(1_000_000);
+        let p50 = histogram.percentile(50.0);
+        assert!((1_000..=1_016).contains(&p50), "{p50}");
+        // the highest percentiles never exceed the largest value recorded
+        assert_eq!(histogram.percentile(100.0), 1_000_000);
+        assert_eq!(histogram.percentile(99.99), 1_000_000);
+    }
+}
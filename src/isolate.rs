@@ -0,0 +1,350 @@
+//
+// Copyright (c) 2025 Nathan Fiedler
+//
+use array_bench::adaptive;
+use array_bench::allocator::Counts;
+use array_bench::memory::Footprint;
+use array_bench::perf::Counters;
+use array_bench::registry::Implementation;
+use array_bench::{Config, Sample, Times};
+use std::env;
+use std::fmt::Write;
+use std::process::{Command, ExitStatus, Stdio};
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Whether, and how often, the benchmarks are run in a new child process.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Isolation {
+    /// Run everything in this process.
+    #[default]
+    None,
+    /// Run all of the runs of each implementation in a new process.
+    Implementation,
+    /// Run every run of every implementation in a new process.
+    Run,
+}
+
+impl FromStr for Isolation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Isolation::None),
+            "implementation" => Ok(Isolation::Implementation),
+            "run" => Ok(Isolation::Run),
+            _ => Err(format!(
+                "unknown isolation: {s} (expected none, implementation, or run)"
+            )),
+        }
+    }
+}
+
+/// Measure the implementation by running this program again as a child
+/// process (or one child per run), which sends the results back over a pipe.
+/// Returns an error describing the failure if a child exits unsuccessfully,
+/// such as when it panics or is killed by the kernel for running out of
+/// memory.
+pub fn measure(
+    imp: &Implementation,
+    config: &Config,
+    isolation: Isolation,
+) -> Result<Vec<Times>, String> {
+    match isolation {
+        Isolation::None => Ok((imp.measure)(config)),
+        Isolation::Implementation => {
+            let times = spawn(imp, config)?;
+            if times.is_empty() {
+                return Err("child process sent no results".to_owned());
+            }
+            Ok(times)
+        }
+        Isolation::Run => {
+            let single = Config {
+                runs: 1,
+                adaptive: None,
+                ..config.clone()
+            };
+            adaptive::repeat(config, || {
+                spawn(imp, &single)?
+                    .pop()
+                    .ok_or_else(|| "child process sent no results".to_owned())
+            })
+        }
+    }
+}
+
+/// Run one child process to measure the implementation.
+fn spawn(imp: &Implementation, config: &Config) -> Result<Vec<Times>, String> {
+    let exe = env::current_exe().map_err(|err| format!("cannot find own executable: {err}"))?;
+    let output = Command::new(exe)
+        .arg("--child")
+        .args(["--only", imp.key])
+        .args(["--size", &config.size.to_string()])
+        .args(["--runs", &config.runs.to_string()])
+        .args(["--seed", &config.seed.to_string()])
+        .args(["--element", config.element.name()])
+        .args(["--mode", config.mode.name()])
+        .args(["--timer", config.timer.name()])
+        .args(config.perf.then_some("--perf"))
+        .args(adaptive_args(config))
+        .stdin(Stdio::null())
+        .stderr(Stdio::inherit())
+        .output()
+        .map_err(|err| format!("cannot start child process: {err}"))?;
+    if !output.status.success() {
+        return Err(describe(output.status));
+    }
+    let text = String::from_utf8(output.stdout)
+        .map_err(|_| "child process sent invalid output".to_owned())?;
+    decode(&text).map_err(|err| format!("child process sent invalid output: {err}"))
+}
+
+/// Arguments that pass on the adaptive settings, if any, to a child process.
+fn adaptive_args(config: &Config) -> Vec<String> {
+    let Some(adaptive) = config.adaptive else {
+        return vec![];
+    };
+    vec![
+        "--adaptive".into(),
+        format!("--warmups={}", adaptive.warmups),
+        format!("--target={}", adaptive.target * 100.0),
+        format!("--budget={}", adaptive.budget.as_secs_f64()),
+        format!("--max-runs={}", adaptive.max_runs),
+    ]
+}
+
+/// Explain how a child process failed.
+fn describe(status: ExitStatus) -> String {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            let hint = match signal {
+                9 => " (SIGKILL, possibly by the out-of-memory killer)",
+                6 => " (SIGABRT, possibly out of memory)",
+                11 => " (SIGSEGV)",
+                _ => "",
+            };
+            return format!("child process killed by signal {signal}{hint}");
+        }
+    }
+    match status.code() {
+        Some(code) => format!("child process exited with status {code}"),
+        None => format!("child process failed: {status}"),
+    }
+}
+
+/// Write the results of every run as text, for the parent process to read.
+///
+/// Each run starts with a `run` line and a `baseline` line, followed by one
+/// line per phase of its name and measurements, ending with the event
+/// counters. Unavailable values are written as `-`.
+pub fn encode(times: &[Times]) -> String {
+    let mut out = String::new();
+    for t in times {
+        out.push_str("run\n");
+        let _ = writeln!(out, "baseline {}", encode_footprint(&t.baseline));
+        for (phase, s) in t.phases() {
+            let a = &s.allocs;
+            let _ = writeln!(
+                out,
+                "{phase} {} {} {} {} {} {} {} {}",
+                s.elapsed.as_nanos(),
+                a.allocs,
+                a.deallocs,
+                a.reallocs,
+                a.bytes_requested,
+                a.bytes_moved,
+                encode_footprint(&s.memory),
+                encode_counters(&s.counters)
+            );
+        }
+    }
+    out
+}
+
+fn encode_footprint(f: &Footprint) -> String {
+    format!(
+        "{} {} {} {}",
+        f.heap,
+        f.heap_peak,
+        encode_optional(f.rss),
+        encode_optional(f.rss_peak)
+    )
+}
+
+fn encode_counters(c: &Counters) -> String {
+    let values: Vec<String> = c.values.iter().map(|v| encode_optional(*v)).collect();
+    values.join(" ")
+}
+
+fn encode_optional(value: Option<u64>) -> String {
+    value.map_or("-".into(), |v| v.to_string())
+}
+
+/// Read the results written by [`encode`].
+pub fn decode(text: &str) -> Result<Vec<Times>, String> {
+    let names: Vec<&str> = Times::default().phases().iter().map(|(n, _)| *n).collect();
+    let mut runs: Vec<(Footprint, Vec<Sample>)> = vec![];
+    for line in text.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        match fields.as_slice() {
+            ["run"] => runs.push((Footprint::default(), vec![])),
+            ["baseline", rest @ ..] => {
+                let run = runs.last_mut().ok_or("baseline before run")?;
+                run.0 = decode_footprint(rest)?;
+            }
+            [phase, elapsed, a, d, r, requested, moved, rest @ ..] => {
+                let run = runs.last_mut().ok_or("phase before run")?;
+                if rest.len() < 4 {
+                    return Err(format!("expected 4 memory fields, got {}", rest.len()));
+                }
+                let (memory, counters) = rest.split_at(4);
+                if names.get(run.1.len()) != Some(phase) {
+                    return Err(format!("unexpected phase {phase}"));
+                }
+                let number = |s: &str| s.parse::<u64>().map_err(|_| format!("bad number {s}"));
+                run.1.push(Sample {
+                    elapsed: Duration::from_nanos(number(elapsed)?),
+                    allocs: Counts {
+                        allocs: number(a)?,
+                        deallocs: number(d)?,
+                        reallocs: number(r)?,
+                        bytes_requested: number(requested)?,
+                        bytes_moved: number(moved)?,
+                    },
+                    memory: decode_footprint(memory)?,
+                    counters: decode_counters(counters)?,
+                });
+            }
+            _ => return Err(format!("unexpected line: {line}")),
+        }
+    }
+    runs.into_iter()
+        .map(|(baseline, samples)| {
+            Times::from_samples(baseline, &samples)
+                .ok_or_else(|| format!("expected {} phases, got {}", names.len(), samples.len()))
+        })
+        .collect()
+}
+
+fn decode_footprint(fields: &[&str]) -> Result<Footprint, String> {
+    let [heap, heap_peak, rss, rss_peak] = fields else {
+        return Err(format!("expected 4 memory fields, got {}", fields.len()));
+    };
+    let number = |s: &str| s.parse::<u64>().map_err(|_| format!("bad number {s}"));
+    Ok(Footprint {
+        heap: number(heap)?,
+        heap_peak: number(heap_peak)?,
+        rss: decode_optional(rss)?,
+        rss_peak: decode_optional(rss_peak)?,
+    })
+}
+
+fn decode_counters(fields: &[&str]) -> Result<Counters, String> {
+    let mut counters = Counters::default();
+    if fields.len() != counters.values.len() {
+        return Err(format!(
+            "expected {} counter fields, got {}",
+            counters.values.len(),
+            fields.len()
+        ));
+    }
+    for (value, field) in counters.values.iter_mut().zip(fields) {
+        *value = decode_optional(field)?;
+    }
+    Ok(counters)
+}
+
+fn decode_optional(s: &str) -> Result<Option<u64>, String> {
+    if s == "-" {
+        Ok(None)
+    } else {
+        s.parse::<u64>()
+            .map(Some)
+            .map_err(|_| format!("bad number {s}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A run whose values differ in every field, with some unavailable.
+    fn run(seed: u64) -> Times {
+        let samples: Vec<Sample> = (0..8)
+            .map(|phase| {
+                let n = seed * 100 + phase * 10;
+                let mut counters = Counters::default();
+                counters.values[0] = Some(n + 7);
+                counters.values[3] = Some(n + 8);
+                Sample {
+                    elapsed: Duration::from_nanos(n + 1),
+                    allocs: Counts {
+                        allocs: n + 2,
+                        deallocs: n + 3,
+                        reallocs: n + 4,
+                        bytes_requested: n + 5,
+                        bytes_moved: n + 6,
+                    },
+                    memory: Footprint {
+                        heap: n + 9,
+                        heap_peak: n + 10,
+                        rss: (phase % 2 == 0).then_some(n + 11),
+                        rss_peak: None,
+                    },
+                    counters,
+                }
+            })
+            .collect();
+        let baseline = Footprint {
+            heap: seed,
+            heap_peak: seed + 1,
+            rss: Some(seed + 2),
+            rss_peak: None,
+        };
+        Times::from_samples(baseline, &samples).unwrap()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let times = vec![run(1), run(2), run(3)];
+        let text = encode(&times);
+        let decoded = decode(&text).unwrap();
+        assert_eq!(decoded.len(), times.len());
+        for (expected, actual) in times.iter().zip(&decoded) {
+            assert_eq!(actual.baseline, expected.baseline);
+            for ((name, e), (actual_name, a)) in expected.phases().iter().zip(actual.phases()) {
+                assert_eq!(actual_name, *name);
+                assert_eq!(a.elapsed, e.elapsed, "{name}");
+                assert_eq!(a.allocs, e.allocs, "{name}");
+                assert_eq!(a.memory, e.memory, "{name}");
+                assert_eq!(a.counters, e.counters, "{name}");
+            }
+        }
+        assert_eq!(encode(&decoded), text);
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let text = encode(&[run(1)]);
+        assert!(decode("").unwrap().is_empty());
+        assert!(decode("baseline 1 2 - -").is_err());
+        assert!(decode("run\nbaseline 1 2 -").is_err());
+        assert!(decode("bogus line").is_err());
+        // missing the last phase
+        let truncated: Vec<&str> = text.lines().take(9).collect();
+        assert!(decode(&truncated.join("\n")).is_err());
+        // phases out of order
+        let swapped = text.replacen("create", "ordered", 1);
+        assert!(decode(&swapped).is_err());
+        // a counter missing or not a number
+        let short = text.replacen(" -\n", "\n", 1);
+        assert_ne!(short, text);
+        assert!(decode(&short).is_err());
+        let bad = text.replacen(" 107 ", " x ", 1);
+        assert_ne!(bad, text);
+        assert!(decode(&bad).is_err());
+    }
+}
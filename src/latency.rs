@@ -0,0 +1,37 @@
+//
+// Copyright (c) 2025 Nathan Fiedler
+//
+use crate::Config;
+use crate::arrays::ResizableArray;
+use crate::element::Element;
+use crate::histogram::Histogram;
+
+/// Push `config.size` values into a new array `config.runs` times, recording
+/// the time taken by each batch of `config.batch` pushes (in nanoseconds).
+pub fn measure<E: Element, A: ResizableArray<E>>(new: fn() -> A, config: &Config) -> Histogram {
+    let mut histogram = Histogram::new();
+    for _ in 0..config.runs {
+        let mut coll = new();
+        push_latencies(&mut coll, config, &mut histogram);
+    }
+    histogram
+}
+
+/// Push `config.size` values into the array, timing each batch of
+/// `config.batch` pushes.
+fn push_latencies<E: Element, A: ResizableArray<E>>(
+    coll: &mut A,
+    config: &Config,
+    histogram: &mut Histogram,
+) {
+    let mut value = 0;
+    while value < config.size {
+        let end = (value + config.batch).min(config.size);
+        let start = config.timer.start();
+        while value < end {
+            coll.push(E::new(value));
+            value += 1;
+        }
+        histogram.record(start.elapsed().as_nanos() as u64);
+    }
+}
@@ -0,0 +1,350 @@
+//
+// Copyright (c) 2025 Nathan Fiedler
+//
+
+//! Harness for measuring resizable array implementations.
+//!
+//! To measure an array type of your own, implement [`arrays::ResizableArray`]
+//! for it and [`registry::ArrayFactory`] for a unit struct that creates it,
+//! describe it with [`registry::Implementation::of`], and pass it to
+//! [`measure`] along with a [`Config`]. The results can then be rendered with
+//! the functions in [`report`]. The allocator and memory figures are only
+//! recorded if the program installs [`allocator::CountingAllocator`] as its
+//! global allocator.
+//!
+//! ```no_run
+//! use array_bench::allocator::CountingAllocator;
+//! use array_bench::registry::{ArrayFactory, Implementation};
+//! use array_bench::{Config, measure, report};
+//!
+//! #[global_allocator]
+//! static GLOBAL: CountingAllocator = CountingAllocator;
+//!
+//! struct Vectors;
+//!
+//! impl ArrayFactory for Vectors {
+//!     type Array<E: array_bench::element::Element> = Vec<E>;
+//!
+//!     fn create<E: array_bench::element::Element>() -> Self::Array<E> {
+//!         Vec::new()
+//!     }
+//! }
+//!
+//! static MINE: Implementation = Implementation::of::<Vectors>("mine", "Vec", &[]);
+//!
+//! let config = Config {
+//!     size: 1_000_000,
+//!     ..Config::default()
+//! };
+//! println!("{}", report::table(&measure(&MINE, &config)));
+//! ```
+use adaptive::Adaptive;
+use arrays::ResizableArray;
+use element::{Element, ElementKind};
+use memory::Footprint;
+use registry::{Implementation, Mode};
+use report::Measurement;
+use std::hint::black_box;
+use std::time::Duration;
+use timer::Timer;
+use workload::Workload;
+
+pub mod adaptive;
+pub mod allocator;
+pub mod arrays;
+pub mod baseline;
+#[cfg(test)]
+mod differential;
+pub mod edits;
+pub mod element;
+pub mod histogram;
+pub mod latency;
+pub mod memory;
+pub mod perf;
+pub mod registry;
+pub mod replay;
+pub mod report;
+pub mod rng;
+pub mod stats;
+pub mod thrash;
+pub mod timer;
+pub mod trace;
+pub mod workload;
+
+/// Settings that apply to every implementation being measured.
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// Number of elements pushed in each run.
+    pub size: usize,
+    /// Number of times each benchmark is repeated.
+    pub runs: usize,
+    /// Settings for repeating the phase benchmarks until their results are
+    /// precise enough, in which case `runs` is not used by them.
+    pub adaptive: Option<Adaptive>,
+    /// Seed for the pseudo-random access order.
+    pub seed: u64,
+    /// Number of pushes timed together in latency mode.
+    pub batch: usize,
+    /// Type of the values stored in the arrays.
+    pub element: ElementKind,
+    /// Whether each run uses a new array or reuses one.
+    pub mode: Mode,
+    /// Sequence of operations performed in workload mode.
+    pub workload: Workload,
+    /// Number of growth boundaries measured in thrash mode.
+    pub boundaries: usize,
+    /// Number of pushes and pops at each boundary in thrash mode.
+    pub cycles: usize,
+    /// Number of elements inserted or removed in each phase of the insert
+    /// and remove benchmark.
+    pub edits: usize,
+    /// Source of the time measurements.
+    pub timer: Timer,
+    /// Whether the event counters are collected during the phase benchmarks.
+    /// They are opened by the thread that measures the first implementation,
+    /// which must be the one that measures the others; call [`perf::open`]
+    /// beforehand to learn which events are unavailable.
+    pub perf: bool,
+}
+
+/// Measurements taken during one phase of a single run.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Sample {
+    pub elapsed: Duration,
+    pub allocs: allocator::Counts,
+    /// Memory in use at the end of the phase, with the peaks during it.
+    pub memory: Footprint,
+    /// Events counted during the phase, if the counters are open.
+    pub counters: perf::Counters,
+}
+
+/// Run the closure, measuring the time it takes with the given timer, the
+/// allocator activity that it causes, the events that it causes (if the
+/// counters are open), and the memory in use.
+pub fn sample(timer: Timer, f: impl FnOnce()) -> Sample {
+    memory::reset_peaks();
+    let before = allocator::counts();
+    let counters = perf::read();
+    let start = timer.start();
+    f();
+    let elapsed = start.elapsed();
+    let counters = perf::read() - counters;
+    let allocs = allocator::counts() - before;
+    let memory = memory::footprint();
+    Sample {
+        elapsed,
+        allocs,
+        memory,
+        counters,
+    }
+}
+
+/// Measurements of every phase of a single run.
+#[derive(Default)]
+pub struct Times {
+    /// Memory in use before the array was constructed.
+    pub baseline: Footprint,
+    pub create: Sample,
+    pub ordered: Sample,
+    pub indexed: Sample,
+    pub random: Sample,
+    pub update: Sample,
+    pub update_random: Sample,
+    pub iter_mut: Sample,
+    pub popall: Sample,
+}
+
+impl Times {
+    /// The name and measurements of each phase, in the order they are run.
+    pub fn phases(&self) -> [(&'static str, Sample); 8] {
+        [
+            ("create", self.create),
+            ("ordered", self.ordered),
+            ("indexed", self.indexed),
+            ("random", self.random),
+            ("update", self.update),
+            ("update-random", self.update_random),
+            ("iter-mut", self.iter_mut),
+            ("pop-all", self.popall),
+        ]
+    }
+
+    /// Assemble the results of a run from its samples, given in the order
+    /// returned by `phases()`. Returns `None` if the number of samples does
+    /// not match the number of phases.
+    pub fn from_samples(baseline: Footprint, samples: &[Sample]) -> Option<Times> {
+        let [
+            create,
+            ordered,
+            indexed,
+            random,
+            update,
+            update_random,
+            iter_mut,
+            popall,
+        ] = *samples
+        else {
+            return None;
+        };
+        Some(Times {
+            baseline,
+            create,
+            ordered,
+            indexed,
+            random,
+            update,
+            update_random,
+            iter_mut,
+            popall,
+        })
+    }
+}
+
+/// Measure pushing `order.len()` values into the given (empty) collection,
+/// visiting them in order via the iterator, then by index, then by index in
+/// the given (random) order, modifying them by index in order and in random
+/// order, modifying them all in place with a single pass, and finally popping
+/// all of them. The `baseline` is the memory in use before the collection was
+/// constructed, and the `timer` measures each phase.
+///
+/// Every value read in a timed loop is loaded and passed to `black_box()`,
+/// so that the optimizer cannot remove the work, and every value modified
+/// is incremented or decremented in place, so that no key is decoded or
+/// encoded. The contents are checked in separate passes that are not timed.
+pub fn benchmark<E: Element, A: ResizableArray<E>>(
+    coll: &mut A,
+    order: &[usize],
+    baseline: Footprint,
+    timer: Timer,
+) -> Times {
+    let size = order.len();
+    let create = sample(timer, || {
+        for value in 0..size {
+            coll.push(E::new(value));
+        }
+    });
+    verify(coll, size, 0);
+
+    // test sequenced access for entire collection
+    let ordered = sample(timer, || {
+        for value in coll.iter() {
+            black_box(value.load());
+        }
+    });
+
+    // test sequenced access through the index API
+    let indexed = sample(timer, || {
+        for index in 0..size {
+            black_box(coll.get(index).map(Element::load));
+        }
+    });
+
+    // test access through the index API in random order
+    let random = sample(timer, || {
+        for &index in order {
+            black_box(coll.get(index).map(Element::load));
+        }
+    });
+
+    // test modifying each element through the index API
+    let update = sample(timer, || {
+        for index in 0..size {
+            coll.get_mut(index).unwrap().increment();
+        }
+    });
+    verify(coll, size, 1);
+
+    // test modifying each element through the index API in random order
+    let update_random = sample(timer, || {
+        for &index in order {
+            coll.get_mut(index).unwrap().increment();
+        }
+    });
+    verify(coll, size, 2);
+
+    // test modifying every element in a single pass
+    let iter_mut = sample(timer, || coll.for_each_mut(Element::decrement));
+    verify(coll, size, 1);
+
+    // test popping all elements from the array
+    let mut popped = 0;
+    let popall = sample(timer, || {
+        while let Some(value) = coll.pop() {
+            black_box(value);
+            popped += 1;
+        }
+    });
+    assert_eq!(popped, size);
+    assert!(coll.is_empty());
+    Times {
+        baseline,
+        create,
+        ordered,
+        indexed,
+        random,
+        update,
+        update_random,
+        iter_mut,
+        popall,
+    }
+}
+
+/// Bytes of element data that the given phase of [`benchmark`] reads or
+/// writes for each element, not counting any memory that the elements own
+/// on the heap. Returns `None` for an unknown phase.
+pub fn bytes_touched(phase: &str, element: ElementKind) -> Option<usize> {
+    match phase {
+        // every value is written into the array, or moved out of it
+        "create" | "pop-all" => Some(element.size()),
+        "ordered" | "indexed" | "random" => Some(element.key_bytes()),
+        // the key is read, then the value is written
+        "update" | "update-random" | "iter-mut" => Some(element.key_bytes() + element.set_bytes()),
+        _ => None,
+    }
+}
+
+/// Check, outside of any timed region, that the array holds `size` elements
+/// and that each represents its index plus `offset`, both when visited by
+/// the iterator and by index.
+fn verify<E: Element, A: ResizableArray<E>>(coll: &A, size: usize, offset: usize) {
+    assert_eq!(coll.len(), size);
+    assert_eq!(coll.iter().count(), size);
+    for (index, value) in coll.iter().enumerate() {
+        assert!(value.matches(index.wrapping_add(offset)));
+        assert!(coll.get(index).unwrap().matches(index.wrapping_add(offset)));
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            size: 100_000_000,
+            runs: 7,
+            adaptive: None,
+            seed: 42,
+            batch: 1,
+            element: ElementKind::Usize,
+            mode: Mode::Cold,
+            workload: Workload::default(),
+            boundaries: 10,
+            cycles: 10_000,
+            edits: 1_000,
+            timer: Timer::Wall,
+            perf: false,
+        }
+    }
+}
+
+/// Perform the configured number of runs of the implementation in this
+/// process and compute the statistics of each phase.
+pub fn measure(implementation: &'static Implementation, config: &Config) -> Measurement {
+    let times = (implementation.measure)(config);
+    Measurement::new(
+        implementation,
+        config.element,
+        config.mode,
+        config.timer,
+        config.size,
+        &times,
+    )
+}
@@ -1,294 +1,300 @@
 //
 // Copyright (c) 2025 Nathan Fiedler
 //
-use extarray::ExtensibleArray;
-use hashed_array_tree::HashedArrayTree;
-use optarray::OptimalArray as BrodnikArray;
-use segment_array::SegmentArray;
-use std::time::{Duration, Instant};
-use tzarrays::general::OptimalArray as GeneralArray;
-use tzarrays::simple::OptimalArray as SimpleArray;
-
-struct Times {
-    create: Duration,
-    ordered: Duration,
-    popall: Duration,
-}
-
-/// Drop the low and high values, return average of those that remain.
-fn compute_average(mut times: Vec<Duration>) -> u64 {
-    times.sort();
-    let total: u64 = times[1..times.len() - 2]
-        .iter()
-        .fold(0, |acc, x| acc + x.as_millis() as u64);
-    total / (times.len() as u64 - 2)
-}
-
-/// Show the average for the collected running times.
-fn display_average_times(times: Vec<Times>) {
-    let create: Vec<Duration> = times.iter().map(|t| t.create).collect();
-    let create = compute_average(create);
-    let ordered: Vec<Duration> = times.iter().map(|t| t.ordered).collect();
-    let ordered = compute_average(ordered);
-    let popall: Vec<Duration> = times.iter().map(|t| t.popall).collect();
-    let popall = compute_average(popall);
-    println!("create: {create}, ordered: {ordered}, pop-all: {popall}",);
-    println!();
-}
-
-fn benchmark_segarray(size: usize) -> Times {
-    let mut coll: SegmentArray<usize> = SegmentArray::new();
-    let start = Instant::now();
-    for value in 0..size {
-        coll.push(value);
-    }
-    let create = start.elapsed();
-
-    // test sequenced access for entire collection
-    let start = Instant::now();
-    for (index, value) in coll.iter().enumerate() {
-        assert_eq!(*value, index);
-    }
-    let ordered = start.elapsed();
-
-    // test popping all elements from the array
-    let start = Instant::now();
-    while !coll.is_empty() {
-        coll.pop();
-    }
-    let popall = start.elapsed();
-    Times {
-        create,
-        ordered,
-        popall,
-    }
-}
-
-fn benchmark_hat(size: usize) -> Times {
-    let mut coll: HashedArrayTree<usize> = HashedArrayTree::new();
-    let start = Instant::now();
-    for value in 0..size {
-        coll.push(value);
-    }
-    let create = start.elapsed();
-
-    // test sequenced access for entire collection
-    let start = Instant::now();
-    for (index, value) in coll.iter().enumerate() {
-        assert_eq!(*value, index);
-    }
-    let ordered = start.elapsed();
-
-    // test popping all elements from the array
-    let start = Instant::now();
-    while !coll.is_empty() {
-        coll.pop();
-    }
-    let popall = start.elapsed();
-    Times {
-        create,
-        ordered,
-        popall,
+use array_bench::Config;
+use array_bench::allocator::CountingAllocator;
+use array_bench::baseline;
+use array_bench::perf;
+use array_bench::registry::{self, Implementation, Mode};
+use array_bench::report::{
+    self, ChurnMeasurement, EditsMeasurement, Format, LatencyMeasurement, Measurement,
+    ReplayMeasurement, Report, ThrashMeasurement,
+};
+use array_bench::trace;
+use std::env;
+use std::fs::File;
+use std::io::BufReader;
+use std::panic::{self, RefUnwindSafe};
+use std::process;
+
+mod cli;
+mod isolate;
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Announce that the implementation is being measured, on standard error for
+/// the machine-readable formats so that standard output remains clean.
+fn progress(format: Format, imp: &Implementation, mode: Option<Mode>) {
+    let label = match mode {
+        Some(mode) => format!("{} ({})", imp.label(), mode.name()),
+        None => imp.label(),
+    };
+    if format == Format::Table {
+        println!("measuring {label}...");
+    } else {
+        eprintln!("measuring {label}...");
     }
 }
 
-fn benchmark_optarray(size: usize) -> Times {
-    let mut coll: BrodnikArray<usize> = BrodnikArray::new();
-    let start = Instant::now();
-    for value in 0..size {
-        coll.push(value);
-    }
-    let create = start.elapsed();
-
-    // test sequenced access for entire collection
-    let start = Instant::now();
-    for (index, value) in coll.iter().enumerate() {
-        assert_eq!(*value, index);
-    }
-    let ordered = start.elapsed();
-
-    // test popping all elements from the array
-    let start = Instant::now();
-    while !coll.is_empty() {
-        coll.pop();
-    }
-    let popall = start.elapsed();
-    Times {
-        create,
-        ordered,
-        popall,
-    }
+/// Measure each implementation with `measure`, then report the results in
+/// the given format. The special benchmarks reach states that the phase
+/// benchmarks never do, so an implementation that panics is reported and
+/// left out while the others are still measured. Returns the number of
+/// implementations that panicked.
+fn run_each<M: Report>(
+    selected: &[&'static Implementation],
+    format: Format,
+    measure: impl Fn(&'static Implementation) -> M + RefUnwindSafe,
+) -> usize {
+    let mut measurements: Vec<M> = vec![];
+    let mut failures = 0;
+    for &imp in selected {
+        progress(format, imp, None);
+        match panic::catch_unwind(|| measure(imp)) {
+            Ok(measurement) => measurements.push(measurement),
+            Err(_) => {
+                eprintln!("error: measuring {} failed: panicked", imp.label());
+                failures += 1;
+            }
+        }
+    }
+    if format == Format::Table {
+        println!();
+    }
+    print!("{}", report::render(&measurements, format));
+    failures
 }
 
-fn benchmark_extarray(size: usize) -> Times {
-    let mut coll: ExtensibleArray<usize> = ExtensibleArray::new();
-    let start = Instant::now();
-    for value in 0..size {
-        coll.push(value);
-    }
-    let create = start.elapsed();
-
-    // test sequenced access for entire collection
-    let start = Instant::now();
-    for (index, value) in coll.iter().enumerate() {
-        assert_eq!(*value, index);
-    }
-    let ordered = start.elapsed();
-
-    // test popping all elements from the array
-    let start = Instant::now();
-    while !coll.is_empty() {
-        coll.pop();
-    }
-    let popall = start.elapsed();
-    Times {
-        create,
-        ordered,
-        popall,
-    }
-}
-
-fn benchmark_general_tarjan(coll: &mut GeneralArray<usize>, size: usize) -> Times {
-    let start = Instant::now();
-    for value in 0..size {
-        coll.push(value);
-    }
-    let create = start.elapsed();
-
-    // test sequenced access for entire collection
-    let start = Instant::now();
-    for (index, value) in coll.iter().enumerate() {
-        assert_eq!(*value, index);
-    }
-    let ordered = start.elapsed();
-
-    // test popping all elements from the array
-    let start = Instant::now();
-    while !coll.is_empty() {
-        coll.pop();
-    }
-    let popall = start.elapsed();
-    Times {
-        create,
-        ordered,
-        popall,
-    }
-}
-
-fn benchmark_simple_tarjan(coll: &mut SimpleArray<usize>, size: usize) -> Times {
-    let start = Instant::now();
-    for value in 0..size {
-        coll.push(value);
-    }
-    let create = start.elapsed();
-
-    // test sequenced access for entire collection
-    let start = Instant::now();
-    for (index, value) in coll.iter().enumerate() {
-        assert_eq!(*value, index);
-    }
-    let ordered = start.elapsed();
-
-    // test popping all elements from the array
-    let start = Instant::now();
-    while !coll.is_empty() {
-        coll.pop();
-    }
-    let popall = start.elapsed();
-    Times {
-        create,
-        ordered,
-        popall,
-    }
-}
-
-fn benchmark_vector(size: usize) -> Times {
-    let start = Instant::now();
-    let mut coll: Vec<usize> = Vec::new();
-    for value in 0..size {
-        coll.push(value);
-    }
-    let create = start.elapsed();
-
-    // test sequenced access for entire collection
-    let start = Instant::now();
-    for (index, value) in coll.iter().enumerate() {
-        assert_eq!(*value, index);
-    }
-    let ordered = start.elapsed();
-
-    // test popping all elements from the vector
-    let start = Instant::now();
-    while !coll.is_empty() {
-        coll.pop();
-    }
-    let popall = start.elapsed();
-    Times {
-        create,
-        ordered,
-        popall,
-    }
+/// Sizes from `min` to `max` inclusive, spaced evenly on a logarithmic scale
+/// with `steps` sizes per decade. The last size is always `max`.
+fn sweep_sizes(min: usize, max: usize, steps: usize) -> Vec<usize> {
+    let mut sizes: Vec<usize> = vec![];
+    for step in 0.. {
+        let size = (min as f64 * 10f64.powf(step as f64 / steps as f64)).round() as usize;
+        if size >= max {
+            break;
+        }
+        if sizes.last() != Some(&size) {
+            sizes.push(size);
+        }
+    }
+    sizes.push(max);
+    sizes
 }
 
 fn main() {
-    let size = 100_000_000;
-
-    println!("measuring std::vec::Vec...");
-    let mut times: Vec<Times> = vec![];
-    for _ in 0..7 {
-        times.push(benchmark_vector(size));
-    }
-    display_average_times(times);
-
-    println!("measuring SegmentArray...");
-    let mut times: Vec<Times> = vec![];
-    for _ in 0..7 {
-        times.push(benchmark_segarray(size));
-    }
-    display_average_times(times);
-
-    println!("measuring HashedArrayTree...");
-    let mut times: Vec<Times> = vec![];
-    for _ in 0..7 {
-        times.push(benchmark_hat(size));
-    }
-    display_average_times(times);
-
-    println!("measuring OptimalArray...");
-    let mut times: Vec<Times> = vec![];
-    for _ in 0..7 {
-        times.push(benchmark_optarray(size));
-    }
-    display_average_times(times);
-
-    println!("measuring ExtensibleArray...");
-    let mut times: Vec<Times> = vec![];
-    for _ in 0..7 {
-        times.push(benchmark_extarray(size));
-    }
-    display_average_times(times);
-
-    println!("measuring GeneralArray (r=3)...");
-    let mut coll: GeneralArray<usize> = GeneralArray::new();
-    let mut times: Vec<Times> = vec![];
-    for _ in 0..7 {
-        times.push(benchmark_general_tarjan(&mut coll, size));
-    }
-    display_average_times(times);
-
-    println!("creating GeneralArray (r=4)...");
-    let mut coll: GeneralArray<usize> = GeneralArray::with_r(4);
-    let mut times: Vec<Times> = vec![];
-    for _ in 0..7 {
-        times.push(benchmark_general_tarjan(&mut coll, size));
-    }
-    display_average_times(times);
-
-    println!("measuring SimpleArray...");
-    let mut coll: SimpleArray<usize> = SimpleArray::new();
-    let mut times: Vec<Times> = vec![];
-    for _ in 0..7 {
-        times.push(benchmark_simple_tarjan(&mut coll, size));
+    let options = match cli::parse_args(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("error: {err}\n\n{}", cli::USAGE);
+            process::exit(2);
+        }
+    };
+    if options.help {
+        println!("{}", cli::USAGE);
+        return;
+    }
+    if options.list {
+        for imp in registry::IMPLEMENTATIONS {
+            println!("{:<12} {}", imp.key, imp.label());
+        }
+        return;
+    }
+    let selected = match registry::select(registry::IMPLEMENTATIONS, &options.only, &options.skip) {
+        Ok(selected) => selected,
+        Err(err) => {
+            eprintln!("error: {err}");
+            process::exit(2);
+        }
+    };
+
+    let config = Config {
+        size: options.size,
+        runs: options.runs,
+        adaptive: options.adaptive,
+        seed: options.seed,
+        batch: options.batch,
+        element: options.element,
+        mode: options.modes[0],
+        workload: options.workload.unwrap_or_default(),
+        boundaries: options.boundaries,
+        cycles: options.cycles,
+        edits: options.edits,
+        timer: options.timer,
+        perf: options.perf,
+    };
+    if config.perf {
+        let unavailable = perf::open();
+        if !unavailable.is_empty() && !options.child {
+            eprintln!("note: counters not available: {}", unavailable.join(", "));
+        }
+    }
+    if options.child {
+        // measure the one selected implementation for the parent process
+        print!("{}", isolate::encode(&(selected[0].measure)(&config)));
+        return;
+    }
+    if options.format == Format::Table {
+        println!("timer: {}", config.timer.describe());
+    } else {
+        eprintln!("timer: {}", config.timer.describe());
+    }
+    let format = options.format;
+    let element = config.element.name();
+    let failures = if options.latency {
+        Some(run_each(&selected, format, |imp| LatencyMeasurement {
+            implementation: imp,
+            element,
+            size: config.size,
+            batch: config.batch,
+            histogram: (imp.latency)(&config),
+        }))
+    } else if let Some(path) = &options.replay {
+        let ops = match File::open(path).and_then(|file| trace::read(BufReader::new(file))) {
+            Ok(ops) => ops,
+            Err(err) => {
+                eprintln!("error: cannot read trace {}: {err}", path.display());
+                process::exit(2);
+            }
+        };
+        Some(run_each(&selected, format, |imp| ReplayMeasurement {
+            implementation: imp,
+            element,
+            ops: ops.len(),
+            replay: (imp.replay)(&config, &ops),
+        }))
+    } else if options.workload.is_some() {
+        Some(run_each(&selected, format, |imp| ChurnMeasurement {
+            implementation: imp,
+            element,
+            workload: config.workload,
+            batch: config.batch,
+            churn: (imp.workload)(&config),
+        }))
+    } else if options.thrash {
+        Some(run_each(&selected, format, |imp| ThrashMeasurement {
+            implementation: imp,
+            element,
+            size: config.size,
+            cycles: config.cycles,
+            thrash: (imp.thrash)(&config),
+        }))
+    } else if options.insert_remove {
+        Some(run_each(&selected, format, |imp| EditsMeasurement {
+            implementation: imp,
+            element,
+            size: config.size,
+            edits: (imp.edits)(&config),
+        }))
+    } else {
+        None
+    };
+    if let Some(failures) = failures {
+        if failures > 0 {
+            eprintln!("{failures} benchmark(s) failed");
+            process::exit(3);
+        }
+        return;
+    }
+    // read the baseline first so that a missing one is reported right away
+    let compare_with = options
+        .compare
+        .as_ref()
+        .map(|name| match baseline::load(name) {
+            Ok(records) => records,
+            Err(err) => {
+                eprintln!("error: cannot read baseline {name}: {err}");
+                process::exit(2);
+            }
+        });
+    let sizes = if options.sweep {
+        sweep_sizes(options.min_size, options.size, options.steps)
+    } else {
+        vec![options.size]
+    };
+    let mut measurements: Vec<Measurement> = vec![];
+    let mut failures = 0;
+    for size in sizes {
+        for &imp in &selected {
+            for &mode in &options.modes {
+                let config = Config {
+                    size,
+                    mode,
+                    ..config.clone()
+                };
+                if options.sweep {
+                    eprintln!(
+                        "measuring {} ({}) with {size} elements...",
+                        imp.label(),
+                        mode.name()
+                    );
+                } else {
+                    progress(options.format, imp, Some(mode));
+                }
+                let times = match isolate::measure(imp, &config, options.isolate) {
+                    Ok(times) => times,
+                    Err(err) => {
+                        eprintln!("error: measuring {} failed: {err}", imp.label());
+                        failures += 1;
+                        continue;
+                    }
+                };
+                let measurement =
+                    Measurement::new(imp, config.element, mode, config.timer, size, &times);
+                if options.format == Format::Table && !options.sweep {
+                    println!("{}", report::table(&measurement));
+                }
+                measurements.push(measurement);
+            }
+        }
+    }
+    match options.format {
+        Format::Table if options.sweep => print!("\n{}", report::sweep_table(&measurements)),
+        Format::Table => (),
+        Format::Json => print!("{}", report::json(&measurements)),
+        Format::Csv => print!("{}", report::csv(&measurements)),
+    }
+
+    if let Some(name) = &options.save_baseline {
+        match baseline::save(name, &measurements) {
+            Ok(path) => eprintln!("saved baseline {name} to {}", path.display()),
+            Err(err) => {
+                eprintln!("error: cannot save baseline {name}: {err}");
+                process::exit(2);
+            }
+        }
+    }
+    if let (Some(name), Some(records)) = (&options.compare, &compare_with) {
+        let comparisons = baseline::compare(records, &measurements);
+        let table = baseline::table(&comparisons, options.threshold);
+        if options.format == Format::Table {
+            print!("\ncompared with baseline {name}:\n{table}");
+        } else {
+            eprint!("compared with baseline {name}:\n{table}");
+        }
+        let regressions = comparisons
+            .iter()
+            .filter(|c| c.is_regression(options.threshold))
+            .count();
+        if comparisons.is_empty() {
+            eprintln!("warning: no results match those in baseline {name}");
+        }
+        if records.iter().any(|r| r.timer != config.timer.name()) {
+            eprintln!(
+                "warning: baseline {name} has results measured with another timer, which are not compared"
+            );
+        }
+        if regressions > 0 {
+            eprintln!(
+                "{regressions} phase(s) regressed by more than {}%",
+                options.threshold
+            );
+            process::exit(1);
+        }
+    }
+    if failures > 0 {
+        eprintln!("{failures} benchmark(s) failed");
+        process::exit(3);
     }
-    display_average_times(times);
 }
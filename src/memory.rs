@@ -0,0 +1,86 @@
+//
+// Copyright (c) 2025 Nathan Fiedler
+//
+use crate::allocator;
+use std::fs;
+
+/// Memory in use by the process at a point in time, along with the largest
+/// amount in use since the peaks were last reset by [`reset_peaks`].
+///
+/// The heap values come from the counting allocator and are exact. The
+/// resident set size is read from `/proc/self/status` and is only available
+/// on Linux.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Footprint {
+    pub heap: u64,
+    pub heap_peak: u64,
+    pub rss: Option<u64>,
+    pub rss_peak: Option<u64>,
+}
+
+impl Footprint {
+    /// Returns the amounts by which this footprint exceeds the `base`.
+    pub fn above(&self, base: &Footprint) -> Footprint {
+        let minus = |a: Option<u64>, b: Option<u64>| Some(a?.saturating_sub(b?));
+        Footprint {
+            heap: self.heap.saturating_sub(base.heap),
+            heap_peak: self.heap_peak.saturating_sub(base.heap),
+            rss: minus(self.rss, base.rss),
+            rss_peak: minus(self.rss_peak, base.rss),
+        }
+    }
+}
+
+/// Returns the average of the given footprints, which must not be empty. The
+/// resident sizes are only available if they are available in every one.
+pub fn average(footprints: &[Footprint]) -> Footprint {
+    let n = footprints.len() as u64;
+    let mean = |f: fn(&Footprint) -> u64| footprints.iter().map(f).sum::<u64>() / n;
+    let mean_opt = |f: fn(&Footprint) -> Option<u64>| {
+        footprints.iter().map(f).sum::<Option<u64>>().map(|t| t / n)
+    };
+    Footprint {
+        heap: mean(|f| f.heap),
+        heap_peak: mean(|f| f.heap_peak),
+        rss: mean_opt(|f| f.rss),
+        rss_peak: mean_opt(|f| f.rss_peak),
+    }
+}
+
+/// Returns the current memory footprint of the process.
+pub fn footprint() -> Footprint {
+    // read the heap usage first, before reading the status file allocates
+    let heap = allocator::live_bytes();
+    let heap_peak = allocator::peak_bytes();
+    let (rss, rss_peak) = resident();
+    Footprint {
+        heap,
+        heap_peak,
+        rss,
+        rss_peak,
+    }
+}
+
+/// Start tracking the peak heap and resident set size from their current
+/// values. Resetting the resident peak requires Linux 4.0 or later; on other
+/// systems the resident peak is that of the lifetime of the process.
+pub fn reset_peaks() {
+    allocator::reset_peak();
+    let _ = fs::write("/proc/self/clear_refs", "5");
+}
+
+/// Read the current (`VmRSS`) and peak (`VmHWM`) resident set size in bytes.
+fn resident() -> (Option<u64>, Option<u64>) {
+    let Ok(status) = fs::read_to_string("/proc/self/status") else {
+        return (None, None);
+    };
+    let field = |name: &str| {
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix(name))
+            .and_then(|rest| rest.trim().strip_suffix("kB"))
+            .and_then(|kb| kb.trim().parse::<u64>().ok())
+            .map(|kb| kb * 1024)
+    };
+    (field("VmRSS:"), field("VmHWM:"))
+}
@@ -0,0 +1,248 @@
+//
+// Copyright (c) 2025 Nathan Fiedler
+//
+use std::ops::{Add, Div, Sub};
+use std::sync::OnceLock;
+
+/// Names of the events that are counted.
+pub const EVENTS: [&str; 6] = [
+    "page-faults",
+    "context-switches",
+    "cpu-migrations",
+    "instructions",
+    "cache-misses",
+    "branch-misses",
+];
+
+/// How each of the [`EVENTS`] is counted.
+const SOURCES: [Source; 6] = [
+    Source::Perf(PERF_TYPE_SOFTWARE, 2, Scope::User),
+    Source::Rusage,
+    Source::Perf(PERF_TYPE_SOFTWARE, 4, Scope::Kernel),
+    Source::Perf(PERF_TYPE_HARDWARE, 1, Scope::User),
+    Source::Perf(PERF_TYPE_HARDWARE, 3, Scope::User),
+    Source::Perf(PERF_TYPE_HARDWARE, 5, Scope::User),
+];
+
+const PERF_TYPE_HARDWARE: u32 = 0;
+const PERF_TYPE_SOFTWARE: u32 = 1;
+
+/// Where an event happens, and so which privileges are needed to count it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Scope {
+    /// Events caused by user space, which any user may count.
+    User,
+    /// Events recorded by the kernel on behalf of the thread, such as CPU
+    /// migrations, which are not counted at all when the kernel is excluded
+    /// and so need `kernel.perf_event_paranoid` to be 1 or less.
+    Kernel,
+}
+
+/// How an event is counted.
+#[derive(Clone, Copy, Debug)]
+enum Source {
+    /// A `perf_event_open` counter of the given type and config.
+    Perf(u32, u64, Scope),
+    /// The voluntary and involuntary context switches of the thread, as
+    /// reported by `getrusage(RUSAGE_THREAD)`, which needs no privileges.
+    Rusage,
+}
+
+/// An event that could be opened.
+#[derive(Clone, Copy, Debug)]
+enum Counter {
+    Fd(i32),
+    Rusage,
+}
+
+/// Values of the counters in [`EVENTS`], in the same order, each of which is
+/// `None` if the counter is not available.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Counters {
+    pub values: [Option<u64>; EVENTS.len()],
+}
+
+impl Counters {
+    /// Returns `true` if any of the counters is available.
+    pub fn any(&self) -> bool {
+        self.values.iter().any(Option::is_some)
+    }
+}
+
+impl Add for Counters {
+    type Output = Counters;
+
+    fn add(self, other: Counters) -> Counters {
+        let mut values = self.values;
+        for (value, other) in values.iter_mut().zip(other.values) {
+            *value = value.zip(other).map(|(a, b)| a + b);
+        }
+        Counters { values }
+    }
+}
+
+impl Sub for Counters {
+    type Output = Counters;
+
+    fn sub(self, other: Counters) -> Counters {
+        let mut values = self.values;
+        for (value, other) in values.iter_mut().zip(other.values) {
+            *value = value.zip(other).map(|(a, b)| a.saturating_sub(b));
+        }
+        Counters { values }
+    }
+}
+
+/// Divide every counter by `n`, rounding down, to get a per-run average.
+impl Div<u64> for Counters {
+    type Output = Counters;
+
+    fn div(self, n: u64) -> Counters {
+        Counters {
+            values: self.values.map(|value| value.map(|v| v / n)),
+        }
+    }
+}
+
+/// The open counters, or `None` for those that could not be opened. Unset
+/// until [`open`] is called.
+static COUNTERS: OnceLock<Vec<Option<Counter>>> = OnceLock::new();
+
+/// Start counting the events for the calling thread, which must be the one
+/// that runs the benchmarks. Returns the names of the events that cannot be
+/// counted, which are then reported as unavailable. Only the first call
+/// opens the counters.
+pub fn open() -> Vec<&'static str> {
+    let counters = COUNTERS.get_or_init(|| {
+        SOURCES
+            .iter()
+            .map(|source| match *source {
+                Source::Perf(kind, config, scope) => {
+                    sys::open(kind, config, scope == Scope::User).map(Counter::Fd)
+                }
+                Source::Rusage => sys::context_switches().map(|_| Counter::Rusage),
+            })
+            .collect()
+    });
+    EVENTS
+        .iter()
+        .zip(counters)
+        .filter(|(_, counter)| counter.is_none())
+        .map(|(name, _)| *name)
+        .collect()
+}
+
+/// Returns the current values of the counters, all of which are `None` if
+/// [`open`] has not been called.
+pub fn read() -> Counters {
+    let mut counters = Counters::default();
+    if let Some(open) = COUNTERS.get() {
+        for (value, counter) in counters.values.iter_mut().zip(open) {
+            *value = match counter {
+                Some(Counter::Fd(fd)) => sys::read(*fd),
+                Some(Counter::Rusage) => sys::context_switches(),
+                None => None,
+            };
+        }
+    }
+    counters
+}
+
+#[cfg(target_os = "linux")]
+mod sys {
+    use std::ffi::{c_long, c_void};
+
+    unsafe extern "C" {
+        fn syscall(number: c_long, ...) -> c_long;
+        #[link_name = "read"]
+        fn read_fd(fd: i32, buf: *mut c_void, count: usize) -> isize;
+        fn getrusage(who: i32, usage: *mut Rusage) -> i32;
+    }
+
+    /// struct rusage: two timevals followed by fourteen longs, of which the
+    /// last two are the voluntary and involuntary context switches.
+    #[repr(C)]
+    struct Rusage {
+        times: [c_long; 4],
+        fields: [c_long; 14],
+    }
+
+    const RUSAGE_THREAD: i32 = 1;
+
+    #[cfg(target_arch = "x86_64")]
+    const SYS_PERF_EVENT_OPEN: c_long = 298;
+    #[cfg(target_arch = "aarch64")]
+    const SYS_PERF_EVENT_OPEN: c_long = 241;
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+    const SYS_PERF_EVENT_OPEN: c_long = -1;
+
+    /// Size of version 7 of `struct perf_event_attr`.
+    const ATTR_SIZE: u32 = 128;
+    const EXCLUDE_KERNEL: u64 = 1 << 5;
+    const EXCLUDE_HV: u64 = 1 << 6;
+    const PERF_FLAG_FD_CLOEXEC: c_long = 8;
+
+    /// Open a counter of the calling thread on any CPU, counting only the
+    /// events in user space if `user_only` is set (as permitted to
+    /// unprivileged users), and return its file descriptor.
+    pub fn open(kind: u32, config: u64, user_only: bool) -> Option<i32> {
+        if SYS_PERF_EVENT_OPEN < 0 {
+            return None;
+        }
+        // struct perf_event_attr, zeroed apart from the fields set below
+        let mut attr = [0u64; ATTR_SIZE as usize / 8];
+        attr[0] = kind as u64 | (ATTR_SIZE as u64) << 32;
+        attr[1] = config;
+        attr[5] = if user_only {
+            EXCLUDE_KERNEL | EXCLUDE_HV
+        } else {
+            EXCLUDE_HV
+        };
+        // SAFETY: the attribute is a valid perf_event_attr of the given size
+        let fd = unsafe {
+            syscall(
+                SYS_PERF_EVENT_OPEN,
+                attr.as_ptr(),
+                0 as c_long,
+                -1 as c_long,
+                -1 as c_long,
+                PERF_FLAG_FD_CLOEXEC,
+            )
+        };
+        (fd >= 0).then_some(fd as i32)
+    }
+
+    /// Read the value of the counter.
+    pub fn read(fd: i32) -> Option<u64> {
+        let mut value = 0u64;
+        // SAFETY: the buffer is a live u64 of the given size
+        let count = unsafe { read_fd(fd, (&raw mut value).cast(), size_of::<u64>()) };
+        (count == size_of::<u64>() as isize).then_some(value)
+    }
+
+    /// Total context switches of the calling thread so far.
+    pub fn context_switches() -> Option<u64> {
+        let mut usage = Rusage {
+            times: [0; 4],
+            fields: [0; 14],
+        };
+        // SAFETY: the pointer refers to a live struct rusage
+        let result = unsafe { getrusage(RUSAGE_THREAD, &mut usage) };
+        (result == 0).then(|| (usage.fields[12] + usage.fields[13]) as u64)
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod sys {
+    pub fn open(_kind: u32, _config: u64, _user_only: bool) -> Option<i32> {
+        None
+    }
+
+    pub fn read(_fd: i32) -> Option<u64> {
+        None
+    }
+
+    pub fn context_switches() -> Option<u64> {
+        None
+    }
+}
@@ -0,0 +1,248 @@
+//
+// Copyright (c) 2025 Nathan Fiedler
+//
+use crate::arrays::ResizableArray;
+use crate::edits::{self, Edits};
+use crate::element::Element;
+use crate::histogram::Histogram;
+use crate::replay::{self, Replay};
+use crate::thrash::{self, Thrash};
+use crate::trace::Op;
+use crate::with_element;
+use crate::workload::{self, Churn};
+use crate::{Config, Times};
+use crate::{adaptive, latency, memory, perf, rng};
+use extarray::ExtensibleArray;
+use hashed_array_tree::HashedArrayTree;
+use optarray::OptimalArray as BrodnikArray;
+use segment_array::SegmentArray;
+use std::convert::Infallible;
+use std::str::FromStr;
+use tzarrays::general::OptimalArray as GeneralArray;
+use tzarrays::simple::OptimalArray as SimpleArray;
+
+/// A resizable array implementation that can be selected for measurement.
+pub struct Implementation {
+    /// Short name used to select the implementation on the command line.
+    pub key: &'static str,
+    /// Name of the array type shown in the results.
+    pub name: &'static str,
+    /// Construction parameters that distinguish this entry from others of
+    /// the same type, as `(name, value)` pairs.
+    pub params: &'static [(&'static str, &'static str)],
+    /// Perform the configured number of runs in the configured mode.
+    pub measure: fn(&Config) -> Vec<Times>,
+    /// Record the latency of pushes for the configured number of runs.
+    pub latency: fn(&Config) -> Histogram,
+    /// Perform the configured mixed workload for the configured number of
+    /// runs.
+    pub workload: fn(&Config) -> Churn,
+    /// Push and pop across the growth boundaries of the array.
+    pub thrash: fn(&Config) -> Thrash,
+    /// Perform the operations of a trace for the configured number of runs.
+    pub replay: fn(&Config, &[Op]) -> Replay,
+    /// Insert and remove elements at various positions for the configured
+    /// number of runs.
+    pub edits: fn(&Config) -> Edits,
+}
+
+impl Implementation {
+    /// Describe the array created by the factory `F`, with the given key to
+    /// select it on the command line, name, and construction parameters.
+    pub const fn of<F: ArrayFactory>(
+        key: &'static str,
+        name: &'static str,
+        params: &'static [(&'static str, &'static str)],
+    ) -> Self {
+        Self {
+            key,
+            name,
+            params,
+            measure: measure::<F>,
+            latency: push_latency::<F>,
+            workload: churn::<F>,
+            thrash: boundaries::<F>,
+            replay: trace::<F>,
+            edits: positions::<F>,
+        }
+    }
+
+    /// The name of the array followed by any parameters, such as
+    /// `GeneralArray (r=3)`.
+    pub fn label(&self) -> String {
+        if self.params.is_empty() {
+            self.name.to_owned()
+        } else {
+            let params: Vec<String> = self
+                .params
+                .iter()
+                .map(|(name, value)| format!("{name}={value}"))
+                .collect();
+            format!("{} ({})", self.name, params.join(", "))
+        }
+    }
+}
+
+/// Creates empty arrays of one implementation, for any element type.
+pub trait ArrayFactory {
+    /// The array type that holds elements of type `E`.
+    type Array<E: Element>: ResizableArray<E>;
+
+    /// Returns a new, empty array.
+    fn create<E: Element>() -> Self::Array<E>;
+}
+
+/// Define a unit struct that implements `ArrayFactory` for the given array
+/// type using the given constructor.
+macro_rules! array_factory {
+    ($factory:ident, $array:ident, $create:expr) => {
+        struct $factory;
+
+        impl ArrayFactory for $factory {
+            type Array<E: Element> = $array<E>;
+
+            fn create<E: Element>() -> Self::Array<E> {
+                $create
+            }
+        }
+    };
+}
+
+array_factory!(Vectors, Vec, Vec::new());
+array_factory!(SegmentArrays, SegmentArray, SegmentArray::new());
+array_factory!(HashedArrayTrees, HashedArrayTree, HashedArrayTree::new());
+array_factory!(BrodnikArrays, BrodnikArray, BrodnikArray::new());
+array_factory!(ExtensibleArrays, ExtensibleArray, ExtensibleArray::new());
+array_factory!(GeneralArraysR3, GeneralArray, GeneralArray::new());
+array_factory!(GeneralArraysR4, GeneralArray, GeneralArray::with_r(4));
+array_factory!(SimpleArrays, SimpleArray, SimpleArray::new());
+
+/// Whether each run starts with a new array or reuses the same one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Mode {
+    /// Every run measures a newly constructed array.
+    #[default]
+    Cold,
+    /// Every run measures the same array, which has already been filled and
+    /// emptied once by an unrecorded run, and so may have retained memory.
+    Warm,
+}
+
+impl Mode {
+    /// Name used to select the mode and to identify it in results.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Mode::Cold => "cold",
+            Mode::Warm => "warm",
+        }
+    }
+}
+
+impl FromStr for Mode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "cold" => Ok(Mode::Cold),
+            "warm" => Ok(Mode::Warm),
+            _ => Err(format!("unknown mode: {s} (expected cold or warm)")),
+        }
+    }
+}
+
+/// Measure the array in the configured mode, opening the event counters
+/// first if they are wanted.
+fn measure<F: ArrayFactory>(config: &Config) -> Vec<Times> {
+    if config.perf {
+        perf::open();
+    }
+    with_element!(config.element, E => match config.mode {
+        Mode::Cold => cold::<F, E>(config),
+        Mode::Warm => warm::<F, E>(config),
+    })
+}
+
+/// Measure a new instance of the array for every run.
+fn cold<F: ArrayFactory, E: Element>(config: &Config) -> Vec<Times> {
+    let order = rng::permutation(config.size, config.seed);
+    let Ok(times) = adaptive::repeat(config, || {
+        let baseline = memory::footprint();
+        Ok::<_, Infallible>(crate::benchmark(
+            &mut F::create::<E>(),
+            &order,
+            baseline,
+            config.timer,
+        ))
+    });
+    times
+}
+
+/// Measure the same instance of the array for every run, after priming it
+/// with one run whose results are discarded.
+fn warm<F: ArrayFactory, E: Element>(config: &Config) -> Vec<Times> {
+    let order = rng::permutation(config.size, config.seed);
+    let baseline = memory::footprint();
+    let mut coll = F::create::<E>();
+    crate::benchmark(&mut coll, &order, baseline, config.timer);
+    let Ok(times) = adaptive::repeat(config, || {
+        Ok::<_, Infallible>(crate::benchmark(&mut coll, &order, baseline, config.timer))
+    });
+    times
+}
+
+/// Record the push latency of new instances of the array.
+fn push_latency<F: ArrayFactory>(config: &Config) -> Histogram {
+    with_element!(config.element, E => latency::measure(F::create::<E>, config))
+}
+
+/// Perform the mixed workload on new instances of the array.
+fn churn<F: ArrayFactory>(config: &Config) -> Churn {
+    with_element!(config.element, E => workload::measure(F::create::<E>, config))
+}
+
+/// Push and pop across the growth boundaries of new instances of the array.
+fn boundaries<F: ArrayFactory>(config: &Config) -> Thrash {
+    with_element!(config.element, E => thrash::measure(F::create::<E>, config))
+}
+
+/// Replay the trace on new instances of the array.
+fn trace<F: ArrayFactory>(config: &Config, ops: &[Op]) -> Replay {
+    with_element!(config.element, E => replay::measure(F::create::<E>, config, ops))
+}
+
+/// Insert and remove elements of new instances of the array.
+fn positions<F: ArrayFactory>(config: &Config) -> Edits {
+    with_element!(config.element, E => edits::measure(F::create::<E>, config))
+}
+
+/// Every implementation known to the harness, in the order they are run.
+pub static IMPLEMENTATIONS: &[Implementation] = &[
+    Implementation::of::<Vectors>("vec", "std::vec::Vec", &[]),
+    Implementation::of::<SegmentArrays>("segment", "SegmentArray", &[]),
+    Implementation::of::<HashedArrayTrees>("hat", "HashedArrayTree", &[]),
+    Implementation::of::<BrodnikArrays>("brodnik", "OptimalArray", &[]),
+    Implementation::of::<ExtensibleArrays>("extensible", "ExtensibleArray", &[]),
+    Implementation::of::<GeneralArraysR3>("general-r3", "GeneralArray", &[("r", "3")]),
+    Implementation::of::<GeneralArraysR4>("general-r4", "GeneralArray", &[("r", "4")]),
+    Implementation::of::<SimpleArrays>("simple", "SimpleArray", &[]),
+];
+
+/// Choose from the given implementations (normally `IMPLEMENTATIONS`) those
+/// named by `only` (or all of them if empty), minus those named by `skip`,
+/// returning an error for any unknown key.
+pub fn select(
+    implementations: &'static [Implementation],
+    only: &[String],
+    skip: &[String],
+) -> Result<Vec<&'static Implementation>, String> {
+    for key in only.iter().chain(skip) {
+        if !implementations.iter().any(|imp| imp.key == key) {
+            return Err(format!("unknown implementation: {key} (see --list)"));
+        }
+    }
+    Ok(implementations
+        .iter()
+        .filter(|imp| only.is_empty() || only.iter().any(|k| k == imp.key))
+        .filter(|imp| !skip.iter().any(|k| k == imp.key))
+        .collect())
+}
@@ -0,0 +1,54 @@
+//
+// Copyright (c) 2025 Nathan Fiedler
+//
+use crate::Config;
+use crate::allocator::{self, Counts};
+use crate::arrays::ResizableArray;
+use crate::element::Element;
+use crate::trace::Op;
+use std::hint::black_box;
+use std::time::Duration;
+
+/// Results of replaying a trace several times.
+pub struct Replay {
+    /// Time taken by each run.
+    pub elapsed: Vec<Duration>,
+    /// Allocator activity, averaged over the runs.
+    pub allocs: Counts,
+}
+
+/// Perform the operations of the trace on a new array `config.runs` times.
+/// The trace must already have been checked by `trace::read()`.
+pub fn measure<E: Element, A: ResizableArray<E>>(
+    new: fn() -> A,
+    config: &Config,
+    ops: &[Op],
+) -> Replay {
+    let mut elapsed = vec![];
+    let mut allocs = Counts::default();
+    for _ in 0..config.runs {
+        let mut coll = new();
+        let before = allocator::counts();
+        let start = config.timer.start();
+        for op in ops {
+            match *op {
+                Op::Push(value) => coll.push(E::new(value)),
+                Op::Pop => {
+                    black_box(coll.pop());
+                }
+                Op::Get(index) => {
+                    black_box(coll.get(index).map(Element::load));
+                }
+                Op::Set(index, value) => coll.get_mut(index).unwrap().set(value),
+                Op::Truncate(len) => coll.truncate(len),
+                Op::Clear => coll.clear(),
+            }
+        }
+        elapsed.push(start.elapsed());
+        allocs = allocs + (allocator::counts() - before);
+    }
+    Replay {
+        elapsed,
+        allocs: allocs / config.runs as u64,
+    }
+}
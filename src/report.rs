@@ -0,0 +1,1283 @@
+//
+// Copyright (c) 2025 Nathan Fiedler
+//
+use crate::Times;
+use crate::allocator::Counts;
+use crate::edits::{EditPhase, Edits};
+use crate::element::ElementKind;
+use crate::histogram::Histogram;
+use crate::memory::{self, Footprint};
+use crate::perf::{self, Counters};
+use crate::registry::{Implementation, Mode};
+use crate::replay::Replay;
+use crate::stats::Summary;
+use crate::thrash::{Boundary, Thrash};
+use crate::timer::Timer;
+use crate::workload::{Churn, Workload};
+use std::fmt::Write;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// How the results are written to standard output.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Format {
+    /// Human readable table, printed as each implementation finishes.
+    #[default]
+    Table,
+    /// A single JSON array of phase records, printed at the end.
+    Json,
+    /// One CSV row per phase record, printed at the end.
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(Format::Table),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!(
+                "unknown format: {s} (expected json, csv, or table)"
+            )),
+        }
+    }
+}
+
+/// Results of one implementation in one of the benchmarks that replace the
+/// phase benchmarks, which are rendered together once every implementation
+/// has been measured.
+pub trait Report: Sized {
+    /// Render the results of every implementation as a table.
+    fn table(measurements: &[Self]) -> String;
+
+    /// Render the results of every implementation as an array of JSON
+    /// objects.
+    fn json(measurements: &[Self]) -> String;
+
+    /// Render the results of every implementation as CSV with a header row.
+    fn csv(measurements: &[Self]) -> String;
+}
+
+/// Render the results of every implementation in the given format.
+pub fn render<M: Report>(measurements: &[M], format: Format) -> String {
+    match format {
+        Format::Table => M::table(measurements),
+        Format::Json => M::json(measurements),
+        Format::Csv => M::csv(measurements),
+    }
+}
+
+macro_rules! impl_report {
+    ($($measurement:ty => $table:ident, $json:ident, $csv:ident;)+) => {
+        $(
+            impl Report for $measurement {
+                fn table(measurements: &[Self]) -> String {
+                    $table(measurements)
+                }
+
+                fn json(measurements: &[Self]) -> String {
+                    $json(measurements)
+                }
+
+                fn csv(measurements: &[Self]) -> String {
+                    $csv(measurements)
+                }
+            }
+        )+
+    };
+}
+
+impl_report! {
+    LatencyMeasurement => latency_table, latency_json, latency_csv;
+    ChurnMeasurement => churn_table, churn_json, churn_csv;
+    ThrashMeasurement => thrash_table, thrash_json, thrash_csv;
+    ReplayMeasurement => replay_table, replay_json, replay_csv;
+    EditsMeasurement => edits_table, edits_json, edits_csv;
+}
+
+/// Raw samples and summary statistics for one phase of a benchmark.
+pub struct PhaseResult {
+    pub phase: &'static str,
+    pub samples: Vec<Duration>,
+    pub summary: Summary,
+    /// Allocator activity during the phase, averaged over the runs.
+    pub allocs: Counts,
+    /// Memory in use at the end of the phase, and the peaks during it, above
+    /// the memory in use before the array was constructed. Averaged over the
+    /// runs.
+    pub memory: Footprint,
+    /// Events counted during the phase, averaged over the runs, each of which
+    /// is `None` if the counter is not available.
+    pub counters: Counters,
+}
+
+/// Rates at which a phase processed the elements, normalised so that they
+/// can be compared across sizes.
+#[derive(Clone, Copy, Debug)]
+pub struct Throughput {
+    pub ns_per_element: f64,
+    pub ops_per_second: f64,
+    /// Bytes of element data read or written per second, not counting any
+    /// memory owned by the elements; see [`crate::bytes_touched`].
+    pub bytes_per_second: Option<f64>,
+}
+
+/// All of the results of measuring one implementation.
+pub struct Measurement {
+    pub implementation: &'static Implementation,
+    pub element: ElementKind,
+    pub mode: Mode,
+    /// Source of the time measurements of every phase.
+    pub timer: Timer,
+    pub size: usize,
+    pub phases: Vec<PhaseResult>,
+}
+
+impl Measurement {
+    /// Gather the samples of each phase from the given runs, which must not
+    /// be empty, and compute their statistics.
+    pub fn new(
+        implementation: &'static Implementation,
+        element: ElementKind,
+        mode: Mode,
+        timer: Timer,
+        size: usize,
+        times: &[Times],
+    ) -> Self {
+        let phases = times[0]
+            .phases()
+            .iter()
+            .enumerate()
+            .map(|(index, (phase, _))| {
+                let samples: Vec<Duration> =
+                    times.iter().map(|t| t.phases()[index].1.elapsed).collect();
+                let summary = Summary::new(&samples);
+                let allocs = times
+                    .iter()
+                    .map(|t| t.phases()[index].1.allocs)
+                    .fold(Counts::default(), |acc, c| acc + c)
+                    / times.len() as u64;
+                let counters = times
+                    .iter()
+                    .map(|t| t.phases()[index].1.counters)
+                    .reduce(|acc, c| acc + c)
+                    .unwrap_or_default()
+                    / times.len() as u64;
+                let footprints: Vec<Footprint> = times
+                    .iter()
+                    .map(|t| t.phases()[index].1.memory.above(&t.baseline))
+                    .collect();
+                PhaseResult {
+                    phase,
+                    samples,
+                    summary,
+                    allocs,
+                    memory: memory::average(&footprints),
+                    counters,
+                }
+            })
+            .collect();
+        Self {
+            implementation,
+            element,
+            mode,
+            timer,
+            size,
+            phases,
+        }
+    }
+
+    /// Median time per element of the phase, number of elements processed
+    /// per second, and the element data read or written in bytes per second.
+    /// Every phase processes each of the elements once, but reads or writes
+    /// a different part of it. Not available if the size or the median time
+    /// is zero.
+    pub fn throughput(&self, result: &PhaseResult) -> Option<Throughput> {
+        let secs = result.summary.median.as_secs_f64();
+        if self.size == 0 || secs == 0.0 {
+            return None;
+        }
+        let elements = self.size as f64;
+        Some(Throughput {
+            ns_per_element: secs * 1e9 / elements,
+            ops_per_second: elements / secs,
+            bytes_per_second: crate::bytes_touched(result.phase, self.element)
+                .map(|bytes| elements * bytes as f64 / secs),
+        })
+    }
+
+    /// Heap bytes held by the array after the `create` phase, divided by the
+    /// number of elements.
+    pub fn bytes_per_element(&self) -> Option<f64> {
+        let create = self.phases.iter().find(|p| p.phase == "create")?;
+        (self.size > 0).then(|| create.memory.heap as f64 / self.size as f64)
+    }
+
+    /// Heap bytes held by the array after the `create` phase that are not
+    /// occupied by elements, as a fraction of the bytes occupied by elements.
+    /// Not available for elements that own heap memory of their own, since
+    /// that memory cannot be told apart from that of the array.
+    pub fn wasted_ratio(&self) -> Option<f64> {
+        let per_element = self.bytes_per_element()?;
+        let element_size = self.element.size();
+        (element_size > 0 && !self.element.owns_heap())
+            .then(|| per_element / element_size as f64 - 1.0)
+    }
+}
+
+/// Format a duration in the largest unit (ns, µs, ms, or s) in which it is
+/// at least one, such as `1.234 ms`.
+pub fn duration(d: Duration) -> String {
+    let nanos = d.as_nanos();
+    if nanos < 1_000 {
+        format!("{nanos} ns")
+    } else if nanos < 1_000_000 {
+        format!("{:.3} µs", nanos as f64 / 1e3)
+    } else if nanos < 1_000_000_000 {
+        format!("{:.3} ms", nanos as f64 / 1e6)
+    } else {
+        format!("{:.3} s", d.as_secs_f64())
+    }
+}
+
+/// Format the relative half-width of a confidence interval as a percentage.
+fn precision(summary: &Summary) -> String {
+    summary
+        .relative_ci()
+        .map_or("n/a".into(), |p| format!("±{:.2}%", p * 100.0))
+}
+
+/// Render the summary statistics for each phase of a measurement as a table.
+pub fn table(measurement: &Measurement) -> String {
+    let mut out = String::new();
+    let runs = measurement.phases.first().map_or(0, |p| p.samples.len());
+    let _ = writeln!(out, "runs: {runs}");
+    let _ = writeln!(
+        out,
+        "{:<14} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>23} {:>10}",
+        "phase", "min", "max", "mean", "median", "trimmed", "stddev", "95% CI", "precision"
+    );
+    for result in &measurement.phases {
+        let summary = &result.summary;
+        let ci = match summary.ci95 {
+            Some((lower, upper)) => format!("{} - {}", duration(lower), duration(upper)),
+            None => "n/a".into(),
+        };
+        let _ = writeln!(
+            out,
+            "{:<14} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>23} {:>10}",
+            result.phase,
+            duration(summary.min),
+            duration(summary.max),
+            duration(summary.mean),
+            duration(summary.median),
+            duration(summary.trimmed_mean),
+            duration(summary.stddev),
+            ci,
+            precision(summary)
+        );
+    }
+    let _ = writeln!(
+        out,
+        "\n{:<14} {:>12} {:>12} {:>12}",
+        "throughput", "ns/element", "Mops/s", "GB/s"
+    );
+    for result in &measurement.phases {
+        match measurement.throughput(result) {
+            Some(t) => {
+                let bandwidth = t
+                    .bytes_per_second
+                    .map_or("n/a".into(), |b| format!("{:.3}", b / 1e9));
+                let _ = writeln!(
+                    out,
+                    "{:<14} {:>12.3} {:>12.3} {:>12}",
+                    result.phase,
+                    t.ns_per_element,
+                    t.ops_per_second / 1e6,
+                    bandwidth
+                );
+            }
+            None => {
+                let _ = writeln!(
+                    out,
+                    "{:<14} {:>12} {:>12} {:>12}",
+                    result.phase, "n/a", "n/a", "n/a"
+                );
+            }
+        }
+    }
+    let _ = writeln!(
+        out,
+        "\n{:<14} {:>12} {:>12} {:>12} {:>16} {:>16}",
+        "allocator", "allocs", "deallocs", "reallocs", "bytes requested", "bytes moved"
+    );
+    for result in &measurement.phases {
+        let allocs = &result.allocs;
+        let _ = writeln!(
+            out,
+            "{:<14} {:>12} {:>12} {:>12} {:>16} {:>16}",
+            result.phase,
+            allocs.allocs,
+            allocs.deallocs,
+            allocs.reallocs,
+            allocs.bytes_requested,
+            allocs.bytes_moved
+        );
+    }
+    let _ = writeln!(
+        out,
+        "\n{:<14} {:>16} {:>16} {:>16} {:>16}",
+        "memory", "heap", "heap peak", "resident", "resident peak"
+    );
+    for result in &measurement.phases {
+        let memory = &result.memory;
+        let optional = |value: Option<u64>| value.map_or("n/a".into(), |v| v.to_string());
+        let _ = writeln!(
+            out,
+            "{:<14} {:>16} {:>16} {:>16} {:>16}",
+            result.phase,
+            memory.heap,
+            memory.heap_peak,
+            optional(memory.rss),
+            optional(memory.rss_peak)
+        );
+    }
+    if measurement.phases.iter().any(|p| p.counters.any()) {
+        let _ = write!(out, "\n{:<14}", "counters");
+        for name in perf::EVENTS {
+            let _ = write!(out, " {name:>16}");
+        }
+        out.push('\n');
+        for result in &measurement.phases {
+            let _ = write!(out, "{:<14}", result.phase);
+            for value in result.counters.values {
+                let value = value.map_or("n/a".into(), |v| v.to_string());
+                let _ = write!(out, " {value:>16}");
+            }
+            out.push('\n');
+        }
+    }
+    if let (Some(per_element), Some(wasted)) =
+        (measurement.bytes_per_element(), measurement.wasted_ratio())
+    {
+        let _ = writeln!(
+            out,
+            "\nbytes per element: {per_element:.3}, wasted space: {:.2}%",
+            wasted * 100.0
+        );
+    }
+    out
+}
+
+/// Render the median time per element of every phase of every measurement
+/// as a table with one row per size and implementation, and one column per
+/// phase, so that the sizes can be compared.
+pub fn sweep_table(measurements: &[Measurement]) -> String {
+    let mut out = String::new();
+    let Some(first) = measurements.first() else {
+        return out;
+    };
+    let _ = write!(out, "{:>12} {:<31}", "size", "median (ns/element)");
+    for result in &first.phases {
+        let _ = write!(out, " {:>13}", result.phase);
+    }
+    out.push('\n');
+    for m in measurements {
+        let label = format!("{} ({})", m.implementation.label(), m.mode.name());
+        let _ = write!(out, "{:>12} {:<31}", m.size, label);
+        for result in &m.phases {
+            let per_element = m
+                .throughput(result)
+                .map_or("n/a".into(), |t| format!("{:.3}", t.ns_per_element));
+            let _ = write!(out, " {per_element:>13}");
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Render every phase of every measurement as an array of JSON objects.
+/// Durations are given in whole nanoseconds.
+pub fn json(measurements: &[Measurement]) -> String {
+    let mut records = vec![];
+    for m in measurements {
+        let imp = m.implementation;
+        for result in &m.phases {
+            let s = &result.summary;
+            let throughput = m.throughput(result);
+            let samples = nanos(&result.samples);
+            let ci95 = match s.ci95 {
+                Some((lower, upper)) => format!("[{}, {}]", lower.as_nanos(), upper.as_nanos()),
+                None => "null".into(),
+            };
+            let counters: String = perf::EVENTS
+                .iter()
+                .zip(result.counters.values)
+                .map(|(name, value)| {
+                    format!(", \"{}\": {}", name.replace('-', "_"), json_optional(value))
+                })
+                .collect();
+            records.push(format!(
+                concat!(
+                    "  {{\"implementation\": {}, \"key\": {}, \"params\": {{{}}}, ",
+                    "\"element\": {}, \"mode\": {}, \"timer\": {}, \"size\": {}, ",
+                    "\"phase\": {}, ",
+                    "\"samples_ns\": [{}], ",
+                    "\"min_ns\": {}, \"max_ns\": {}, \"mean_ns\": {}, \"median_ns\": {}, ",
+                    "\"stddev_ns\": {}, \"trimmed_mean_ns\": {}, \"ci95_ns\": {}, ",
+                    "\"ci95_relative\": {}, ",
+                    "\"allocs\": {}, \"deallocs\": {}, \"reallocs\": {}, ",
+                    "\"bytes_requested\": {}, \"bytes_moved\": {}, ",
+                    "\"heap_bytes\": {}, \"heap_peak_bytes\": {}, ",
+                    "\"rss_bytes\": {}, \"rss_peak_bytes\": {}, ",
+                    "\"bytes_per_element\": {}, \"wasted_ratio\": {}, ",
+                    "\"ns_per_element\": {}, \"ops_per_second\": {}, ",
+                    "\"gb_per_second\": {}{}}}"
+                ),
+                json_string(imp.name),
+                json_string(imp.key),
+                json_params(imp),
+                json_string(m.element.name()),
+                json_string(m.mode.name()),
+                json_string(m.timer.name()),
+                m.size,
+                json_string(result.phase),
+                samples.join(", "),
+                s.min.as_nanos(),
+                s.max.as_nanos(),
+                s.mean.as_nanos(),
+                s.median.as_nanos(),
+                s.stddev.as_nanos(),
+                s.trimmed_mean.as_nanos(),
+                ci95,
+                json_optional(s.relative_ci()),
+                result.allocs.allocs,
+                result.allocs.deallocs,
+                result.allocs.reallocs,
+                result.allocs.bytes_requested,
+                result.allocs.bytes_moved,
+                result.memory.heap,
+                result.memory.heap_peak,
+                json_optional(result.memory.rss),
+                json_optional(result.memory.rss_peak),
+                json_optional(m.bytes_per_element()),
+                json_optional(m.wasted_ratio()),
+                json_optional(throughput.map(|t| t.ns_per_element)),
+                json_optional(throughput.map(|t| t.ops_per_second)),
+                json_optional(throughput.and_then(|t| t.bytes_per_second).map(|b| b / 1e9)),
+                counters
+            ));
+        }
+    }
+    json_array(&records)
+}
+
+/// Render every phase of every measurement as CSV with a header row. The
+/// parameters are written as `name=value` pairs, and the raw samples as a
+/// list, both separated by semicolons. Durations are in whole nanoseconds.
+pub fn csv(measurements: &[Measurement]) -> String {
+    let mut out = String::from(concat!(
+        "implementation,key,params,element,mode,timer,size,phase,samples_ns,min_ns,max_ns,",
+        "mean_ns,median_ns,stddev_ns,trimmed_mean_ns,ci95_lower_ns,ci95_upper_ns,",
+        "ci95_relative,allocs,deallocs,reallocs,bytes_requested,bytes_moved,",
+        "heap_bytes,heap_peak_bytes,rss_bytes,rss_peak_bytes,bytes_per_element,wasted_ratio,",
+        "ns_per_element,ops_per_second,gb_per_second"
+    ));
+    for name in perf::EVENTS {
+        let _ = write!(out, ",{}", name.replace('-', "_"));
+    }
+    out.push('\n');
+    for m in measurements {
+        let imp = m.implementation;
+        for result in &m.phases {
+            let s = &result.summary;
+            let throughput = m.throughput(result);
+            let samples = nanos(&result.samples);
+            let (lower, upper) = match s.ci95 {
+                Some((lower, upper)) => {
+                    (lower.as_nanos().to_string(), upper.as_nanos().to_string())
+                }
+                None => (String::new(), String::new()),
+            };
+            let counters: String = result
+                .counters
+                .values
+                .iter()
+                .map(|value| format!(",{}", csv_optional(*value)))
+                .collect();
+            let _ = writeln!(
+                out,
+                "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}{}",
+                csv_field(imp.name),
+                csv_field(imp.key),
+                csv_params(imp),
+                csv_field(m.element.name()),
+                m.mode.name(),
+                m.timer.name(),
+                m.size,
+                csv_field(result.phase),
+                samples.join(";"),
+                s.min.as_nanos(),
+                s.max.as_nanos(),
+                s.mean.as_nanos(),
+                s.median.as_nanos(),
+                s.stddev.as_nanos(),
+                s.trimmed_mean.as_nanos(),
+                lower,
+                upper,
+                csv_optional(s.relative_ci()),
+                result.allocs.allocs,
+                result.allocs.deallocs,
+                result.allocs.reallocs,
+                result.allocs.bytes_requested,
+                result.allocs.bytes_moved,
+                result.memory.heap,
+                result.memory.heap_peak,
+                csv_optional(result.memory.rss),
+                csv_optional(result.memory.rss_peak),
+                csv_optional(m.bytes_per_element()),
+                csv_optional(m.wasted_ratio()),
+                csv_optional(throughput.map(|t| t.ns_per_element)),
+                csv_optional(throughput.map(|t| t.ops_per_second)),
+                csv_optional(throughput.and_then(|t| t.bytes_per_second).map(|b| b / 1e9)),
+                counters
+            );
+        }
+    }
+    out
+}
+
+/// Percentiles of the push latency that are reported, with their labels.
+const LATENCY_PERCENTILES: [(&str, f64); 4] = [
+    ("p50", 50.0),
+    ("p99", 99.0),
+    ("p99.9", 99.9),
+    ("p99.99", 99.99),
+];
+
+/// Distribution of the push latency of one implementation.
+pub struct LatencyMeasurement {
+    pub implementation: &'static Implementation,
+    pub element: &'static str,
+    pub size: usize,
+    /// Number of pushes timed together as one sample.
+    pub batch: usize,
+    pub histogram: Histogram,
+}
+
+/// Render the latency percentiles of every implementation as a table, one
+/// row per implementation.
+pub fn latency_table(measurements: &[LatencyMeasurement]) -> String {
+    let mut out = String::new();
+    let batch = measurements.first().map_or(1, |m| m.batch);
+    let _ = write!(
+        out,
+        "{:<24} {:>12}",
+        format!("push latency (ns/{batch})"),
+        "samples"
+    );
+    for (label, _) in LATENCY_PERCENTILES {
+        let _ = write!(out, " {label:>10}");
+    }
+    let _ = writeln!(out, " {:>12}", "max");
+    for m in measurements {
+        let h = &m.histogram;
+        let _ = write!(out, "{:<24} {:>12}", m.implementation.label(), h.count());
+        for (_, percent) in LATENCY_PERCENTILES {
+            let _ = write!(out, " {:>10}", h.percentile(percent));
+        }
+        let _ = writeln!(out, " {:>12}", h.max());
+    }
+    out
+}
+
+/// Render the latency percentiles of every implementation as an array of
+/// JSON objects, in nanoseconds per batch of pushes.
+pub fn latency_json(measurements: &[LatencyMeasurement]) -> String {
+    let mut records = vec![];
+    for m in measurements {
+        let imp = m.implementation;
+        let h = &m.histogram;
+        let percentiles: Vec<String> = LATENCY_PERCENTILES
+            .iter()
+            .map(|(label, percent)| format!("\"{label}_ns\": {}", h.percentile(*percent)))
+            .collect();
+        records.push(format!(
+            concat!(
+                "  {{\"implementation\": {}, \"key\": {}, \"params\": {{{}}}, ",
+                "\"element\": {}, \"size\": {}, \"batch\": {}, \"samples\": {}, ",
+                "\"min_ns\": {}, {}, \"max_ns\": {}}}"
+            ),
+            json_string(imp.name),
+            json_string(imp.key),
+            json_params(imp),
+            json_string(m.element),
+            m.size,
+            m.batch,
+            h.count(),
+            h.min(),
+            percentiles.join(", "),
+            h.max()
+        ));
+    }
+    json_array(&records)
+}
+
+/// Render the latency percentiles of every implementation as CSV with a
+/// header row, in nanoseconds per batch of pushes.
+pub fn latency_csv(measurements: &[LatencyMeasurement]) -> String {
+    let mut out = String::from("implementation,key,params,element,size,batch,samples,min_ns");
+    for (label, _) in LATENCY_PERCENTILES {
+        let _ = write!(out, ",{label}_ns");
+    }
+    out.push_str(",max_ns\n");
+    for m in measurements {
+        let imp = m.implementation;
+        let h = &m.histogram;
+        let _ = write!(
+            out,
+            "{},{},{},{},{},{},{},{}",
+            csv_field(imp.name),
+            csv_field(imp.key),
+            csv_params(imp),
+            csv_field(m.element),
+            m.size,
+            m.batch,
+            h.count(),
+            h.min()
+        );
+        for (_, percent) in LATENCY_PERCENTILES {
+            let _ = write!(out, ",{}", h.percentile(percent));
+        }
+        let _ = writeln!(out, ",{}", h.max());
+    }
+    out
+}
+
+/// Results of the mixed workload for one implementation.
+pub struct ChurnMeasurement {
+    pub implementation: &'static Implementation,
+    pub element: &'static str,
+    pub workload: Workload,
+    /// Number of operations timed together as one sample.
+    pub batch: usize,
+    pub churn: Churn,
+}
+
+impl ChurnMeasurement {
+    /// Operations per second, based on the median time of the runs.
+    pub fn throughput(&self) -> f64 {
+        let median = Summary::new(&self.churn.elapsed).median;
+        self.churn.ops as f64 / median.as_secs_f64()
+    }
+}
+
+/// Render the throughput, latency percentiles, and allocator activity of
+/// every implementation under the mixed workload as a table.
+pub fn churn_table(measurements: &[ChurnMeasurement]) -> String {
+    let mut out = String::new();
+    let Some(first) = measurements.first() else {
+        return out;
+    };
+    let _ = writeln!(
+        out,
+        "workload {} with {} operations, {} element(s) at most\n",
+        first.workload.label(),
+        first.churn.ops,
+        first.churn.max_len
+    );
+    let _ = write!(
+        out,
+        "{:<24} {:>12}",
+        format!("latency (ns/{})", first.batch),
+        "Mops/s"
+    );
+    for (label, _) in LATENCY_PERCENTILES {
+        let _ = write!(out, " {label:>10}");
+    }
+    let _ = writeln!(
+        out,
+        " {:>12} {:>10} {:>10} {:>10}",
+        "max", "allocs", "deallocs", "reallocs"
+    );
+    for m in measurements {
+        let h = &m.churn.histogram;
+        let _ = write!(
+            out,
+            "{:<24} {:>12.3}",
+            m.implementation.label(),
+            m.throughput() / 1e6
+        );
+        for (_, percent) in LATENCY_PERCENTILES {
+            let _ = write!(out, " {:>10}", h.percentile(percent));
+        }
+        let allocs = &m.churn.allocs;
+        let _ = writeln!(
+            out,
+            " {:>12} {:>10} {:>10} {:>10}",
+            h.max(),
+            allocs.allocs,
+            allocs.deallocs,
+            allocs.reallocs
+        );
+    }
+    out
+}
+
+/// Render the results of the mixed workload as an array of JSON objects,
+/// with latencies in nanoseconds per batch of operations.
+pub fn churn_json(measurements: &[ChurnMeasurement]) -> String {
+    let mut records = vec![];
+    for m in measurements {
+        let imp = m.implementation;
+        let h = &m.churn.histogram;
+        let samples = nanos(&m.churn.elapsed);
+        let percentiles: Vec<String> = LATENCY_PERCENTILES
+            .iter()
+            .map(|(label, percent)| format!("\"{label}_ns\": {}", h.percentile(*percent)))
+            .collect();
+        records.push(format!(
+            concat!(
+                "  {{\"implementation\": {}, \"key\": {}, \"params\": {{{}}}, ",
+                "\"element\": {}, \"workload\": {}, \"push_ratio\": {}, \"period\": {}, ",
+                "\"ops\": {}, \"max_len\": {}, \"batch\": {}, \"samples_ns\": [{}], ",
+                "\"ops_per_sec\": {:.0}, \"min_ns\": {}, {}, \"max_ns\": {}, ",
+                "\"allocs\": {}, \"deallocs\": {}, \"reallocs\": {}}}"
+            ),
+            json_string(imp.name),
+            json_string(imp.key),
+            json_params(imp),
+            json_string(m.element),
+            json_string(m.workload.pattern.name()),
+            m.workload.push_ratio,
+            m.workload.period,
+            m.churn.ops,
+            m.churn.max_len,
+            m.batch,
+            samples.join(", "),
+            m.throughput(),
+            h.min(),
+            percentiles.join(", "),
+            h.max(),
+            m.churn.allocs.allocs,
+            m.churn.allocs.deallocs,
+            m.churn.allocs.reallocs
+        ));
+    }
+    json_array(&records)
+}
+
+/// Render the results of the mixed workload as CSV with a header row, with
+/// latencies in nanoseconds per batch of operations.
+pub fn churn_csv(measurements: &[ChurnMeasurement]) -> String {
+    let mut out = String::from(concat!(
+        "implementation,key,params,element,workload,push_ratio,period,ops,max_len,",
+        "batch,samples_ns,ops_per_sec,min_ns"
+    ));
+    for (label, _) in LATENCY_PERCENTILES {
+        let _ = write!(out, ",{label}_ns");
+    }
+    out.push_str(",max_ns,allocs,deallocs,reallocs\n");
+    for m in measurements {
+        let imp = m.implementation;
+        let h = &m.churn.histogram;
+        let samples = nanos(&m.churn.elapsed);
+        let _ = write!(
+            out,
+            "{},{},{},{},{},{},{},{},{},{},{},{:.0},{}",
+            csv_field(imp.name),
+            csv_field(imp.key),
+            csv_params(imp),
+            csv_field(m.element),
+            m.workload.pattern.name(),
+            m.workload.push_ratio,
+            m.workload.period,
+            m.churn.ops,
+            m.churn.max_len,
+            m.batch,
+            samples.join(";"),
+            m.throughput(),
+            h.min()
+        );
+        for (_, percent) in LATENCY_PERCENTILES {
+            let _ = write!(out, ",{}", h.percentile(percent));
+        }
+        let _ = writeln!(
+            out,
+            ",{},{},{},{}",
+            h.max(),
+            m.churn.allocs.allocs,
+            m.churn.allocs.deallocs,
+            m.churn.allocs.reallocs
+        );
+    }
+    out
+}
+
+/// Results of the boundary thrashing benchmark for one implementation.
+pub struct ThrashMeasurement {
+    pub implementation: &'static Implementation,
+    pub element: &'static str,
+    /// Length below which the growth boundaries were sought.
+    pub size: usize,
+    /// Number of push and pop pairs performed at each boundary.
+    pub cycles: usize,
+    pub thrash: Thrash,
+}
+
+/// Fraction of the push and pop pairs that caused an allocation or
+/// reallocation above which a boundary is considered to be thrashing.
+const THRASH_LIMIT: f64 = 0.5;
+
+/// Calls to `alloc` and `realloc` per push and pop pair.
+fn allocs_per_cycle(boundary: &Boundary, cycles: usize) -> f64 {
+    (boundary.allocs.allocs + boundary.allocs.reallocs) as f64 / cycles as f64
+}
+
+/// Median time taken by one push and pop pair, in nanoseconds.
+fn nanos_per_cycle(boundary: &Boundary, cycles: usize) -> f64 {
+    Summary::new(&boundary.elapsed).median.as_nanos() as f64 / cycles as f64
+}
+
+/// Render the results of the boundary thrashing benchmark as a table, one
+/// row per boundary measured, marking those that allocate on most cycles.
+pub fn thrash_table(measurements: &[ThrashMeasurement]) -> String {
+    let mut out = String::new();
+    for m in measurements {
+        let _ = writeln!(
+            out,
+            "{}: {} growth boundaries below {}, {} cycles at each",
+            m.implementation.label(),
+            m.thrash.found,
+            m.size,
+            m.cycles
+        );
+        let _ = writeln!(
+            out,
+            "{:>14} {:>12} {:>12} {:>12} {:>12} {:>14}  verdict",
+            "length", "ns/cycle", "allocs", "deallocs", "reallocs", "allocs/cycle"
+        );
+        for b in &m.thrash.boundaries {
+            let per_cycle = allocs_per_cycle(b, m.cycles);
+            let verdict = if per_cycle > THRASH_LIMIT {
+                "THRASHING"
+            } else {
+                "ok"
+            };
+            let _ = writeln!(
+                out,
+                "{:>14} {:>12.1} {:>12} {:>12} {:>12} {:>14.3}  {}",
+                b.len,
+                nanos_per_cycle(b, m.cycles),
+                b.allocs.allocs,
+                b.allocs.deallocs,
+                b.allocs.reallocs,
+                per_cycle,
+                verdict
+            );
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Render the results of the boundary thrashing benchmark as an array of
+/// JSON objects, one per implementation and boundary.
+pub fn thrash_json(measurements: &[ThrashMeasurement]) -> String {
+    let mut records = vec![];
+    for m in measurements {
+        let imp = m.implementation;
+        for b in &m.thrash.boundaries {
+            let samples = nanos(&b.elapsed);
+            records.push(format!(
+                concat!(
+                    "  {{\"implementation\": {}, \"key\": {}, \"params\": {{{}}}, ",
+                    "\"element\": {}, \"size\": {}, \"boundaries_found\": {}, ",
+                    "\"length\": {}, \"cycles\": {}, \"samples_ns\": [{}], ",
+                    "\"ns_per_cycle\": {:.3}, \"allocs\": {}, \"deallocs\": {}, ",
+                    "\"reallocs\": {}, \"allocs_per_cycle\": {:.6}, \"thrashing\": {}}}"
+                ),
+                json_string(imp.name),
+                json_string(imp.key),
+                json_params(imp),
+                json_string(m.element),
+                m.size,
+                m.thrash.found,
+                b.len,
+                m.cycles,
+                samples.join(", "),
+                nanos_per_cycle(b, m.cycles),
+                b.allocs.allocs,
+                b.allocs.deallocs,
+                b.allocs.reallocs,
+                allocs_per_cycle(b, m.cycles),
+                allocs_per_cycle(b, m.cycles) > THRASH_LIMIT
+            ));
+        }
+    }
+    json_array(&records)
+}
+
+/// Render the results of the boundary thrashing benchmark as CSV with a
+/// header row, one record per implementation and boundary.
+pub fn thrash_csv(measurements: &[ThrashMeasurement]) -> String {
+    let mut out = String::from(concat!(
+        "implementation,key,params,element,size,boundaries_found,length,cycles,",
+        "samples_ns,ns_per_cycle,allocs,deallocs,reallocs,allocs_per_cycle,thrashing\n"
+    ));
+    for m in measurements {
+        let imp = m.implementation;
+        for b in &m.thrash.boundaries {
+            let samples = nanos(&b.elapsed);
+            let _ = writeln!(
+                out,
+                "{},{},{},{},{},{},{},{},{},{:.3},{},{},{},{:.6},{}",
+                csv_field(imp.name),
+                csv_field(imp.key),
+                csv_params(imp),
+                csv_field(m.element),
+                m.size,
+                m.thrash.found,
+                b.len,
+                m.cycles,
+                samples.join(";"),
+                nanos_per_cycle(b, m.cycles),
+                b.allocs.allocs,
+                b.allocs.deallocs,
+                b.allocs.reallocs,
+                allocs_per_cycle(b, m.cycles),
+                allocs_per_cycle(b, m.cycles) > THRASH_LIMIT
+            );
+        }
+    }
+    out
+}
+
+/// Results of replaying a trace with one implementation.
+pub struct ReplayMeasurement {
+    pub implementation: &'static Implementation,
+    pub element: &'static str,
+    /// Number of operations in the trace.
+    pub ops: usize,
+    pub replay: Replay,
+}
+
+/// Render the time taken to replay the trace, and the allocator activity,
+/// as a table with one row per implementation.
+pub fn replay_table(measurements: &[ReplayMeasurement]) -> String {
+    let mut out = String::new();
+    let Some(first) = measurements.first() else {
+        return out;
+    };
+    let _ = writeln!(out, "replay of {} operations\n", first.ops);
+    let _ = writeln!(
+        out,
+        "{:<24} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10}",
+        "replay", "min", "max", "mean", "median", "stddev", "Mops/s", "allocs", "deallocs"
+    );
+    for m in measurements {
+        let summary = Summary::new(&m.replay.elapsed);
+        let _ = writeln!(
+            out,
+            "{:<24} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10.3} {:>10} {:>10}",
+            m.implementation.label(),
+            duration(summary.min),
+            duration(summary.max),
+            duration(summary.mean),
+            duration(summary.median),
+            duration(summary.stddev),
+            m.ops as f64 / summary.median.as_secs_f64() / 1e6,
+            m.replay.allocs.allocs,
+            m.replay.allocs.deallocs
+        );
+    }
+    out
+}
+
+/// Render the results of replaying the trace as an array of JSON objects,
+/// one per implementation, with durations in nanoseconds.
+pub fn replay_json(measurements: &[ReplayMeasurement]) -> String {
+    let mut records = vec![];
+    for m in measurements {
+        let imp = m.implementation;
+        let summary = Summary::new(&m.replay.elapsed);
+        let samples = nanos(&m.replay.elapsed);
+        records.push(format!(
+            concat!(
+                "  {{\"implementation\": {}, \"key\": {}, \"params\": {{{}}}, ",
+                "\"element\": {}, \"ops\": {}, \"samples_ns\": [{}], \"min_ns\": {}, ",
+                "\"max_ns\": {}, \"mean_ns\": {}, \"median_ns\": {}, \"stddev_ns\": {}, ",
+                "\"allocs\": {}, \"deallocs\": {}, \"reallocs\": {}}}"
+            ),
+            json_string(imp.name),
+            json_string(imp.key),
+            json_params(imp),
+            json_string(m.element),
+            m.ops,
+            samples.join(", "),
+            summary.min.as_nanos(),
+            summary.max.as_nanos(),
+            summary.mean.as_nanos(),
+            summary.median.as_nanos(),
+            summary.stddev.as_nanos(),
+            m.replay.allocs.allocs,
+            m.replay.allocs.deallocs,
+            m.replay.allocs.reallocs
+        ));
+    }
+    json_array(&records)
+}
+
+/// Render the results of replaying the trace as CSV with a header row, one
+/// record per implementation, with durations in nanoseconds.
+pub fn replay_csv(measurements: &[ReplayMeasurement]) -> String {
+    let mut out = String::from(concat!(
+        "implementation,key,params,element,ops,samples_ns,min_ns,max_ns,mean_ns,",
+        "median_ns,stddev_ns,allocs,deallocs,reallocs\n"
+    ));
+    for m in measurements {
+        let imp = m.implementation;
+        let summary = Summary::new(&m.replay.elapsed);
+        let samples = nanos(&m.replay.elapsed);
+        let _ = writeln!(
+            out,
+            "{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            csv_field(imp.name),
+            csv_field(imp.key),
+            csv_params(imp),
+            csv_field(m.element),
+            m.ops,
+            samples.join(";"),
+            summary.min.as_nanos(),
+            summary.max.as_nanos(),
+            summary.mean.as_nanos(),
+            summary.median.as_nanos(),
+            summary.stddev.as_nanos(),
+            m.replay.allocs.allocs,
+            m.replay.allocs.deallocs,
+            m.replay.allocs.reallocs
+        );
+    }
+    out
+}
+
+/// Results of the insert and remove benchmark for one implementation.
+pub struct EditsMeasurement {
+    pub implementation: &'static Implementation,
+    pub element: &'static str,
+    /// Number of elements in the array before each phase.
+    pub size: usize,
+    pub edits: Edits,
+}
+
+impl EditsMeasurement {
+    /// Median time taken to insert or remove one element in the given
+    /// phase, in nanoseconds, or `None` if the operation is not offered.
+    pub fn nanos_per_edit(&self, phase: &EditPhase) -> Option<f64> {
+        let elapsed = phase.elapsed.as_ref()?;
+        let median = Summary::new(elapsed).median;
+        Some(median.as_nanos() as f64 / self.edits.edits.max(1) as f64)
+    }
+}
+
+/// Time per element of each phase relative to that of `Vec`, if `Vec` was
+/// measured and both offer the operation.
+fn relative_to_vec(
+    measurements: &[EditsMeasurement],
+    m: &EditsMeasurement,
+    index: usize,
+) -> Option<f64> {
+    let vec = measurements
+        .iter()
+        .find(|m| m.implementation.key == "vec")?;
+    let baseline = vec.nanos_per_edit(&vec.edits.phases[index])?;
+    let nanos = m.nanos_per_edit(&m.edits.phases[index])?;
+    (baseline > 0.0).then(|| nanos / baseline)
+}
+
+/// Render the median time per element of each phase of the insert and
+/// remove benchmark as a table with one row per phase and one column per
+/// implementation, followed by the same relative to `Vec`.
+pub fn edits_table(measurements: &[EditsMeasurement]) -> String {
+    let mut out = String::new();
+    let Some(first) = measurements.first() else {
+        return out;
+    };
+    let _ = writeln!(
+        out,
+        "{} edits per phase on {} elements\n",
+        first.edits.edits, first.size
+    );
+    let has_vec = measurements.iter().any(|m| m.implementation.key == "vec");
+    let tables: &[(&str, bool)] = &[("ns/element", false), ("relative to Vec", true)];
+    for &(title, relative) in tables {
+        if relative && !has_vec {
+            continue;
+        }
+        let _ = write!(out, "{title:<16}");
+        for m in measurements {
+            let _ = write!(out, " {:>12}", m.implementation.key);
+        }
+        out.push('\n');
+        for (index, phase) in first.edits.phases.iter().enumerate() {
+            let _ = write!(out, "{:<16}", phase.phase);
+            for m in measurements {
+                let cell = if relative {
+                    relative_to_vec(measurements, m, index).map(|r| format!("{r:.2}x"))
+                } else {
+                    m.nanos_per_edit(&m.edits.phases[index])
+                        .map(|n| format!("{n:.1}"))
+                };
+                let _ = write!(out, " {:>12}", cell.as_deref().unwrap_or("unsupported"));
+            }
+            out.push('\n');
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Render the results of the insert and remove benchmark as an array of
+/// JSON objects, one per implementation and phase, with durations in
+/// nanoseconds. The times are `null` for operations that are not offered.
+pub fn edits_json(measurements: &[EditsMeasurement]) -> String {
+    let mut records = vec![];
+    for m in measurements {
+        let imp = m.implementation;
+        for (index, phase) in m.edits.phases.iter().enumerate() {
+            let samples = phase
+                .elapsed
+                .as_ref()
+                .map(|elapsed| format!("[{}]", nanos(elapsed).join(", ")));
+            records.push(format!(
+                concat!(
+                    "  {{\"implementation\": {}, \"key\": {}, \"params\": {{{}}}, ",
+                    "\"element\": {}, \"size\": {}, \"edits\": {}, \"phase\": {}, ",
+                    "\"supported\": {}, \"samples_ns\": {}, \"ns_per_element\": {}, ",
+                    "\"relative_to_vec\": {}}}"
+                ),
+                json_string(imp.name),
+                json_string(imp.key),
+                json_params(imp),
+                json_string(m.element),
+                m.size,
+                m.edits.edits,
+                json_string(phase.phase),
+                phase.elapsed.is_some(),
+                json_optional(samples),
+                json_optional(m.nanos_per_edit(phase).map(|n| format!("{n:.3}"))),
+                json_optional(relative_to_vec(measurements, m, index).map(|r| format!("{r:.4}")))
+            ));
+        }
+    }
+    json_array(&records)
+}
+
+/// Render the results of the insert and remove benchmark as CSV with a
+/// header row, one record per implementation and phase, with durations in
+/// nanoseconds. The times are empty for operations that are not offered.
+pub fn edits_csv(measurements: &[EditsMeasurement]) -> String {
+    let mut out = String::from(concat!(
+        "implementation,key,params,element,size,edits,phase,supported,samples_ns,",
+        "ns_per_element,relative_to_vec\n"
+    ));
+    for m in measurements {
+        let imp = m.implementation;
+        for (index, phase) in m.edits.phases.iter().enumerate() {
+            let samples = nanos(phase.elapsed.as_deref().unwrap_or_default());
+            let _ = writeln!(
+                out,
+                "{},{},{},{},{},{},{},{},{},{},{}",
+                csv_field(imp.name),
+                csv_field(imp.key),
+                csv_params(imp),
+                csv_field(m.element),
+                m.size,
+                m.edits.edits,
+                phase.phase,
+                phase.elapsed.is_some(),
+                samples.join(";"),
+                csv_optional(m.nanos_per_edit(phase).map(|n| format!("{n:.3}"))),
+                csv_optional(relative_to_vec(measurements, m, index).map(|r| format!("{r:.4}")))
+            );
+        }
+    }
+    out
+}
+
+/// The raw samples in whole nanoseconds.
+fn nanos(samples: &[Duration]) -> Vec<String> {
+    samples.iter().map(|d| d.as_nanos().to_string()).collect()
+}
+
+/// The parameters of the implementation as the members of a JSON object.
+fn json_params(imp: &Implementation) -> String {
+    let params: Vec<String> = imp
+        .params
+        .iter()
+        .map(|(name, value)| format!("{}: {}", json_string(name), json_string(value)))
+        .collect();
+    params.join(", ")
+}
+
+/// The parameters of the implementation as a single CSV field of
+/// `name=value` pairs separated by semicolons.
+fn csv_params(imp: &Implementation) -> String {
+    let params: Vec<String> = imp
+        .params
+        .iter()
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect();
+    csv_field(&params.join(";"))
+}
+
+/// A JSON array of the records, one per line.
+fn json_array(records: &[String]) -> String {
+    if records.is_empty() {
+        "[]\n".into()
+    } else {
+        format!("[\n{}\n]\n", records.join(",\n"))
+    }
+}
+
+/// Format an optional value for JSON, using `null` when absent.
+fn json_optional<T: ToString>(value: Option<T>) -> String {
+    value.map_or("null".into(), |v| v.to_string())
+}
+
+/// Format an optional value for CSV, leaving the field empty when absent.
+fn csv_optional<T: ToString>(value: Option<T>) -> String {
+    value.map_or(String::new(), |v| v.to_string())
+}
+
+/// Quote and escape a string for inclusion in JSON.
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Quote a CSV field if it contains a delimiter, quote, or line break.
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_owned()
+    }
+}
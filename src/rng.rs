@@ -0,0 +1,55 @@
+//
+// Copyright (c) 2025 Nathan Fiedler
+//
+
+/// Small, fast, seedable pseudo-random number generator (SplitMix64).
+///
+/// The same seed always produces the same sequence, which keeps the
+/// benchmarks repeatable across runs and machines.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Returns the next pseudo-random 64-bit value.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a value uniformly distributed in `0..bound`, which must be
+    /// greater than zero.
+    pub fn below(&mut self, bound: usize) -> usize {
+        // Lemire's multiply-shift with rejection to avoid modulo bias
+        let bound = bound as u64;
+        let threshold = bound.wrapping_neg() % bound;
+        loop {
+            let product = (self.next_u64() as u128) * (bound as u128);
+            if (product as u64) >= threshold {
+                return (product >> 64) as usize;
+            }
+        }
+    }
+
+    /// Shuffle the slice in place (Fisher-Yates).
+    pub fn shuffle<T>(&mut self, values: &mut [T]) {
+        for i in (1..values.len()).rev() {
+            values.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+/// Returns the indices `0..len` in a pseudo-random order determined by `seed`.
+pub fn permutation(len: usize, seed: u64) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..len).collect();
+    Rng::new(seed).shuffle(&mut order);
+    order
+}
@@ -0,0 +1,250 @@
+//
+// Copyright (c) 2025 Nathan Fiedler
+//
+use std::time::Duration;
+
+/// Summary statistics for a set of timing samples.
+///
+/// All values retain the full nanosecond precision of the samples.
+#[derive(Clone, Copy, Debug)]
+pub struct Summary {
+    pub min: Duration,
+    pub max: Duration,
+    pub mean: Duration,
+    pub median: Duration,
+    /// Sample standard deviation (Bessel corrected).
+    pub stddev: Duration,
+    /// Mean of the samples after discarding the same number of low and high
+    /// values; see [`trim_count`].
+    pub trimmed_mean: Duration,
+    /// Lower and upper bound of the 95% confidence interval for the mean,
+    /// using Student's t-distribution. Requires at least two samples.
+    pub ci95: Option<(Duration, Duration)>,
+}
+
+impl Summary {
+    /// Compute the statistics for the given samples, which must not be empty.
+    pub fn new(samples: &[Duration]) -> Self {
+        assert!(!samples.is_empty(), "cannot summarize zero samples");
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let count = sorted.len();
+        let mean = average(&sorted);
+        let median = if count % 2 == 1 {
+            sorted[count / 2]
+        } else {
+            average(&sorted[count / 2 - 1..=count / 2])
+        };
+        let trim = trim_count(count);
+        let trimmed_mean = average(&sorted[trim..count - trim]);
+        let stddev = if count > 1 {
+            let mean_ns = mean.as_nanos() as f64;
+            let sum_sq: f64 = sorted
+                .iter()
+                .map(|d| {
+                    let delta = d.as_nanos() as f64 - mean_ns;
+                    delta * delta
+                })
+                .sum();
+            (sum_sq / (count - 1) as f64).sqrt()
+        } else {
+            0.0
+        };
+        let ci95 = if count > 1 {
+            let margin = t_critical_95(count - 1) * stddev / (count as f64).sqrt();
+            let mean_ns = mean.as_nanos() as f64;
+            Some((
+                from_nanos_f64((mean_ns - margin).max(0.0)),
+                from_nanos_f64(mean_ns + margin),
+            ))
+        } else {
+            None
+        };
+        Self {
+            min: sorted[0],
+            max: sorted[count - 1],
+            mean,
+            median,
+            stddev: from_nanos_f64(stddev),
+            trimmed_mean,
+            ci95,
+        }
+    }
+
+    /// Half-width of the 95% confidence interval of the mean, relative to
+    /// the mean, such as 0.01 for ±1%. Requires at least two samples.
+    pub fn relative_ci(&self) -> Option<f64> {
+        let (_, upper) = self.ci95?;
+        let mean = self.mean.as_nanos() as f64;
+        let margin = upper.as_nanos() as f64 - mean;
+        Some(if mean > 0.0 { margin / mean } else { 0.0 })
+    }
+}
+
+/// Number of samples dropped from each end for the trimmed mean: 10% of the
+/// samples, but at least one from each end when there are three or more.
+pub fn trim_count(count: usize) -> usize {
+    if count < 3 { 0 } else { (count / 10).max(1) }
+}
+
+/// Welch's t-test for a difference between the means of two sets of samples
+/// that may have unequal variances. Returns `Some(true)` if the difference is
+/// significant at the 95% level, or `None` if either set has fewer than two
+/// samples.
+pub fn significantly_different(a: &[Duration], b: &[Duration]) -> Option<bool> {
+    if a.len() < 2 || b.len() < 2 {
+        return None;
+    }
+    let moments = |samples: &[Duration]| {
+        let n = samples.len() as f64;
+        let mean = samples.iter().map(|d| d.as_nanos() as f64).sum::<f64>() / n;
+        let var = samples
+            .iter()
+            .map(|d| (d.as_nanos() as f64 - mean).powi(2))
+            .sum::<f64>()
+            / (n - 1.0);
+        (mean, var / n)
+    };
+    let (mean_a, se2_a) = moments(a);
+    let (mean_b, se2_b) = moments(b);
+    let se2 = se2_a + se2_b;
+    if se2 == 0.0 {
+        return Some(mean_a != mean_b);
+    }
+    let t = (mean_a - mean_b).abs() / se2.sqrt();
+    // Welch-Satterthwaite approximation, rounded down to stay conservative
+    let df =
+        se2 * se2 / (se2_a * se2_a / (a.len() - 1) as f64 + se2_b * se2_b / (b.len() - 1) as f64);
+    Some(t > t_critical_95((df.floor() as usize).max(1)))
+}
+
+/// Arithmetic mean of the samples, exact to the nanosecond.
+fn average(samples: &[Duration]) -> Duration {
+    let total: u128 = samples.iter().map(Duration::as_nanos).sum();
+    from_nanos_u128(total / samples.len() as u128)
+}
+
+fn from_nanos_u128(nanos: u128) -> Duration {
+    let secs = (nanos / 1_000_000_000) as u64;
+    Duration::new(secs, (nanos % 1_000_000_000) as u32)
+}
+
+fn from_nanos_f64(nanos: f64) -> Duration {
+    Duration::from_secs_f64(nanos / 1e9)
+}
+
+/// Two-sided 95% critical value of Student's t-distribution for the given
+/// degrees of freedom. Between table entries the value for the next lower
+/// tabulated degree of freedom is used, which errs on the wide side.
+fn t_critical_95(df: usize) -> f64 {
+    const TABLE: [f64; 30] = [
+        12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160,
+        2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056,
+        2.052, 2.048, 2.045, 2.042,
+    ];
+    match df {
+        0 => f64::INFINITY,
+        1..=30 => TABLE[df - 1],
+        31..=40 => 2.042,
+        41..=60 => 2.021,
+        61..=120 => 2.000,
+        _ => 1.980,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nanos(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|&n| Duration::from_nanos(n)).collect()
+    }
+
+    #[test]
+    fn test_trim_count() {
+        assert_eq!(trim_count(1), 0);
+        assert_eq!(trim_count(2), 0);
+        assert_eq!(trim_count(3), 1);
+        assert_eq!(trim_count(19), 1);
+        assert_eq!(trim_count(20), 2);
+    }
+
+    #[test]
+    fn test_trimmed_mean() {
+        // the outlier is dropped along with the lowest sample
+        let summary = Summary::new(&nanos(&[100, 2_000_000, 200]));
+        assert_eq!(summary.trimmed_mean, Duration::from_nanos(200));
+        let summary = Summary::new(&nanos(&[100, 300]));
+        assert_eq!(summary.trimmed_mean, Duration::from_nanos(200));
+        let summary = Summary::new(&nanos(&[1, 10, 10, 10, 10, 10, 10, 10, 10, 1000]));
+        assert_eq!(summary.trimmed_mean, Duration::from_nanos(10));
+    }
+
+    #[test]
+    fn test_median() {
+        let summary = Summary::new(&nanos(&[40, 10, 30, 20]));
+        assert_eq!(summary.median, Duration::from_nanos(25));
+        let summary = Summary::new(&nanos(&[30, 10, 20]));
+        assert_eq!(summary.median, Duration::from_nanos(20));
+        assert_eq!(summary.min, Duration::from_nanos(10));
+        assert_eq!(summary.max, Duration::from_nanos(30));
+    }
+
+    #[test]
+    fn test_confidence_interval() {
+        // mean 20000, standard deviation 10000, t = 4.303 for 2 degrees of
+        // freedom, so the margin is 4.303 * 10000 / sqrt(3) = 24843.5
+        let summary = Summary::new(&nanos(&[10_000, 20_000, 30_000]));
+        assert_eq!(summary.mean, Duration::from_nanos(20_000));
+        assert_eq!(summary.stddev, Duration::from_nanos(10_000));
+        let (lower, upper) = summary.ci95.unwrap();
+        assert_eq!(lower, Duration::ZERO);
+        assert!(upper.as_nanos().abs_diff(44_843) <= 1, "{upper:?}");
+        let relative = summary.relative_ci().unwrap();
+        assert!((relative - 1.242).abs() < 0.001, "{relative}");
+        // no interval from a single sample
+        assert!(Summary::new(&nanos(&[5])).ci95.is_none());
+    }
+
+    #[test]
+    fn test_t_critical_95() {
+        assert_eq!(t_critical_95(0), f64::INFINITY);
+        assert_eq!(t_critical_95(1), 12.706);
+        assert_eq!(t_critical_95(2), 4.303);
+        assert_eq!(t_critical_95(30), 2.042);
+        assert_eq!(t_critical_95(35), 2.042);
+        assert_eq!(t_critical_95(100), 2.000);
+        assert_eq!(t_critical_95(1000), 1.980);
+    }
+
+    #[test]
+    fn test_welch_identical() {
+        let a = nanos(&[100, 110, 120, 130]);
+        assert_eq!(significantly_different(&a, &a), Some(false));
+        let b = nanos(&[50, 50, 50]);
+        assert_eq!(significantly_different(&b, &b), Some(false));
+    }
+
+    #[test]
+    fn test_welch_different() {
+        let a = nanos(&[100, 110, 120, 130]);
+        let b = nanos(&[1000, 1010, 1020, 1030, 1040]);
+        assert_eq!(significantly_different(&a, &b), Some(true));
+        assert_eq!(significantly_different(&b, &a), Some(true));
+        // overlapping and noisy samples are not significantly different
+        let c = nanos(&[90, 150, 100, 140]);
+        assert_eq!(significantly_different(&a, &c), Some(false));
+        // without variance, any difference in the means is significant
+        let d = nanos(&[60, 60, 60]);
+        let e = nanos(&[50, 50, 50]);
+        assert_eq!(significantly_different(&d, &e), Some(true));
+    }
+
+    #[test]
+    fn test_welch_too_few_samples() {
+        let a = nanos(&[100]);
+        let b = nanos(&[100, 200]);
+        assert_eq!(significantly_different(&a, &b), None);
+        assert_eq!(significantly_different(&b, &a), None);
+    }
+}
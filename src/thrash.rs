@@ -0,0 +1,96 @@
+//
+// Copyright (c) 2025 Nathan Fiedler
+//
+use crate::Config;
+use crate::allocator::{self, Counts};
+use crate::arrays::ResizableArray;
+use crate::element::Element;
+use std::time::Duration;
+
+/// Results of pushing and popping across one growth boundary.
+pub struct Boundary {
+    /// Length of the array at which the next push allocates.
+    pub len: usize,
+    /// Time taken by all of the cycles, for each run.
+    pub elapsed: Vec<Duration>,
+    /// Allocator activity during the cycles, averaged over the runs.
+    pub allocs: Counts,
+}
+
+/// Results of the boundary thrashing benchmark for one implementation.
+pub struct Thrash {
+    /// Number of growth boundaries found below the configured size.
+    pub found: usize,
+    /// Results for the boundaries that were measured.
+    pub boundaries: Vec<Boundary>,
+}
+
+/// Find the lengths below `config.size` at which pushing to the array
+/// allocates, then at up to `config.boundaries` of them, spread evenly over
+/// those found, perform `config.cycles` pairs of push and pop that cross the
+/// boundary, `config.runs` times.
+pub fn measure<E: Element, A: ResizableArray<E>>(new: fn() -> A, config: &Config) -> Thrash {
+    let found = find_boundaries::<E, A>(new(), config.size);
+    let chosen = spread(&found, config.boundaries);
+    let boundaries = chosen
+        .into_iter()
+        .map(|len| {
+            let mut elapsed = vec![];
+            let mut allocs = Counts::default();
+            for _ in 0..config.runs {
+                let mut coll = new();
+                for value in 0..len {
+                    coll.push(E::new(value));
+                }
+                // reuse the one value so the element itself never allocates
+                let mut value = E::new(len);
+                let before = allocator::counts();
+                let start = config.timer.start();
+                for _ in 0..config.cycles {
+                    coll.push(value);
+                    value = coll.pop().unwrap();
+                }
+                elapsed.push(start.elapsed());
+                allocs = allocs + (allocator::counts() - before);
+            }
+            Boundary {
+                len,
+                elapsed,
+                allocs: allocs / config.runs as u64,
+            }
+        })
+        .collect();
+    Thrash {
+        found: found.len(),
+        boundaries,
+    }
+}
+
+/// Push `size` values into the array, returning the lengths at which a push
+/// caused the array to allocate or reallocate memory.
+fn find_boundaries<E: Element, A: ResizableArray<E>>(mut coll: A, size: usize) -> Vec<usize> {
+    let mut found = vec![];
+    for len in 0..size {
+        // create the value first so that its own allocation is not counted
+        let value = E::new(len);
+        let before = allocator::counts();
+        coll.push(value);
+        let after = allocator::counts() - before;
+        if after.allocs > 0 || after.reallocs > 0 {
+            found.push(len);
+        }
+    }
+    found
+}
+
+/// Choose up to `count` of the values, spread evenly from first to last.
+fn spread(values: &[usize], count: usize) -> Vec<usize> {
+    if values.len() <= count {
+        return values.to_vec();
+    }
+    let mut chosen: Vec<usize> = (0..count)
+        .map(|i| values[i * (values.len() - 1) / (count - 1).max(1)])
+        .collect();
+    chosen.dedup();
+    chosen
+}
@@ -0,0 +1,195 @@
+//
+// Copyright (c) 2025 Nathan Fiedler
+//
+use std::hint::black_box;
+use std::str::FromStr;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// Source of the time measurements.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Timer {
+    /// Monotonic wall clock time.
+    #[default]
+    Wall,
+    /// CPU time consumed by the calling thread, which excludes the time
+    /// spent waiting while another process runs (Linux only).
+    ThreadCpu,
+    /// Time stamp counter cycles read with `rdtsc`, converted to time using
+    /// the frequency of the counter (x86_64 only).
+    Cycles,
+}
+
+impl Timer {
+    /// Every timer, in the order shown to the user.
+    pub const ALL: [Timer; 3] = [Timer::Wall, Timer::ThreadCpu, Timer::Cycles];
+
+    /// Name used to select the timer and to identify it in results.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Timer::Wall => "wall",
+            Timer::ThreadCpu => "cpu",
+            Timer::Cycles => "cycles",
+        }
+    }
+
+    /// Returns `true` if the timer can be used on this platform.
+    pub fn is_available(&self) -> bool {
+        match self {
+            Timer::Wall => true,
+            Timer::ThreadCpu => cfg!(target_os = "linux"),
+            Timer::Cycles => cfg!(target_arch = "x86_64"),
+        }
+    }
+
+    /// Start measuring an interval. The timer must be available.
+    pub fn start(self) -> Stopwatch {
+        // calibrate now so that it does not happen within the interval
+        let overhead = self.overhead();
+        if self == Timer::Cycles {
+            cycles_per_second();
+        }
+        Stopwatch {
+            timer: self,
+            overhead,
+            start: self.read(),
+        }
+    }
+
+    /// Returns the current reading of the timer, in its own units:
+    /// nanoseconds for the clocks and cycles for the time stamp counter.
+    fn read(self) -> u64 {
+        match self {
+            Timer::Wall => {
+                static EPOCH: OnceLock<Instant> = OnceLock::new();
+                EPOCH.get_or_init(Instant::now).elapsed().as_nanos() as u64
+            }
+            Timer::ThreadCpu => thread_cpu_nanos(),
+            Timer::Cycles => cycles(),
+        }
+    }
+
+    /// Convert a difference between two readings to a duration.
+    fn to_duration(self, ticks: u64) -> Duration {
+        match self {
+            Timer::Wall | Timer::ThreadCpu => Duration::from_nanos(ticks),
+            Timer::Cycles => Duration::from_secs_f64(ticks as f64 / cycles_per_second()),
+        }
+    }
+
+    /// The smallest difference between two consecutive readings, in the
+    /// units of the timer, which is subtracted from every interval measured.
+    /// Calibrated once, on first use.
+    fn overhead(self) -> u64 {
+        static OVERHEAD: [OnceLock<u64>; 3] = [OnceLock::new(), OnceLock::new(), OnceLock::new()];
+        let index = Timer::ALL.iter().position(|t| *t == self).unwrap();
+        *OVERHEAD[index].get_or_init(|| {
+            (0..10_000)
+                .map(|_| {
+                    let start = black_box(self.read());
+                    black_box(self.read()).saturating_sub(start)
+                })
+                .min()
+                .unwrap_or(0)
+        })
+    }
+
+    /// The timer and its calibration, such as `cycles (2.995 GHz,
+    /// overhead 7 ns)`.
+    pub fn describe(&self) -> String {
+        let overhead = self.to_duration(self.overhead()).as_nanos();
+        match self {
+            Timer::Cycles => format!(
+                "{} ({:.3} GHz, overhead {overhead} ns)",
+                self.name(),
+                cycles_per_second() / 1e9
+            ),
+            _ => format!("{} (overhead {overhead} ns)", self.name()),
+        }
+    }
+}
+
+impl FromStr for Timer {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let timer = Timer::ALL
+            .into_iter()
+            .find(|timer| timer.name() == s)
+            .ok_or_else(|| format!("unknown timer: {s} (expected wall, cpu, or cycles)"))?;
+        if timer.is_available() {
+            Ok(timer)
+        } else {
+            Err(format!("timer {s} is not available on this platform"))
+        }
+    }
+}
+
+/// A running measurement of an interval, like `Instant` but for any timer.
+#[derive(Clone, Copy, Debug)]
+pub struct Stopwatch {
+    timer: Timer,
+    overhead: u64,
+    start: u64,
+}
+
+impl Stopwatch {
+    /// Time since the stopwatch was started, less the overhead of the timer.
+    pub fn elapsed(&self) -> Duration {
+        let ticks = self.timer.read().saturating_sub(self.start);
+        self.timer.to_duration(ticks.saturating_sub(self.overhead))
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn thread_cpu_nanos() -> u64 {
+    #[repr(C)]
+    struct Timespec {
+        tv_sec: i64,
+        tv_nsec: i64,
+    }
+    unsafe extern "C" {
+        fn clock_gettime(clock: i32, time: *mut Timespec) -> i32;
+    }
+    const CLOCK_THREAD_CPUTIME_ID: i32 = 3;
+    let mut time = Timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    // SAFETY: the clock is valid and the pointer refers to a live Timespec
+    let result = unsafe { clock_gettime(CLOCK_THREAD_CPUTIME_ID, &mut time) };
+    assert_eq!(result, 0, "clock_gettime(CLOCK_THREAD_CPUTIME_ID) failed");
+    time.tv_sec as u64 * 1_000_000_000 + time.tv_nsec as u64
+}
+
+#[cfg(not(target_os = "linux"))]
+fn thread_cpu_nanos() -> u64 {
+    unreachable!("the thread CPU timer is only available on Linux")
+}
+
+#[cfg(target_arch = "x86_64")]
+fn cycles() -> u64 {
+    // SAFETY: every x86_64 processor has the time stamp counter
+    #[allow(unused_unsafe)]
+    unsafe {
+        std::arch::x86_64::_rdtsc()
+    }
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn cycles() -> u64 {
+    unreachable!("the cycle timer is only available on x86_64")
+}
+
+/// Frequency of the time stamp counter, measured once against the wall
+/// clock over 50 milliseconds.
+fn cycles_per_second() -> f64 {
+    static FREQUENCY: OnceLock<f64> = OnceLock::new();
+    *FREQUENCY.get_or_init(|| {
+        let start = Instant::now();
+        let first = cycles();
+        while start.elapsed() < Duration::from_millis(50) {}
+        let ticks = cycles() - first;
+        ticks as f64 / start.elapsed().as_secs_f64()
+    })
+}
@@ -0,0 +1,285 @@
+//
+// Copyright (c) 2025 Nathan Fiedler
+//
+
+//! Traces of the operations an application performs on its arrays.
+//!
+//! A trace is a text file with one operation per line:
+//!
+//! ```text
+//! # lines starting with '#' and blank lines are ignored
+//! push 42
+//! pop
+//! get 0
+//! set 0 7
+//! truncate 10
+//! clear
+//! ```
+//!
+//! Values and indices are unsigned integers. An application records a trace
+//! with a [`Recorder`], which can then be replayed against every array
+//! implementation with `array-bench replay FILE`.
+
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+/// A single operation on an array.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Op {
+    /// Append a value to the back of the array.
+    Push(usize),
+    /// Remove the last value, if any.
+    Pop,
+    /// Read the value at an index.
+    Get(usize),
+    /// Replace the value at an index.
+    Set(usize, usize),
+    /// Shorten the array to the given length.
+    Truncate(usize),
+    /// Remove every value.
+    Clear,
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Op::Push(value) => write!(f, "push {value}"),
+            Op::Pop => write!(f, "pop"),
+            Op::Get(index) => write!(f, "get {index}"),
+            Op::Set(index, value) => write!(f, "set {index} {value}"),
+            Op::Truncate(len) => write!(f, "truncate {len}"),
+            Op::Clear => write!(f, "clear"),
+        }
+    }
+}
+
+/// Parse a single line of a trace, returning `None` for a blank line or a
+/// comment.
+fn parse_line(line: &str) -> Result<Option<Op>, String> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(None);
+    }
+    let mut words = line.split_whitespace();
+    let name = words.next().unwrap_or_default();
+    let args: Vec<&str> = words.collect();
+    let number = |text: &str| {
+        text.parse::<usize>()
+            .map_err(|_| format!("invalid number: {text}"))
+    };
+    let op = match (name, args.as_slice()) {
+        ("push", [value]) => Op::Push(number(value)?),
+        ("pop", []) => Op::Pop,
+        ("get", [index]) => Op::Get(number(index)?),
+        ("set", [index, value]) => Op::Set(number(index)?, number(value)?),
+        ("truncate", [len]) => Op::Truncate(number(len)?),
+        ("clear", []) => Op::Clear,
+        ("push" | "pop" | "get" | "set" | "truncate" | "clear", _) => {
+            return Err(format!("wrong number of arguments for {name}"));
+        }
+        _ => return Err(format!("unknown operation: {name}")),
+    };
+    Ok(Some(op))
+}
+
+/// Read a trace, checking that every `get` and `set` refers to an element
+/// that exists at that point, so that replaying it cannot fail.
+pub fn read(reader: impl BufRead) -> io::Result<Vec<Op>> {
+    let invalid = |number: usize, msg: String| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("line {}: {msg}", number + 1),
+        )
+    };
+    let mut ops = vec![];
+    let mut len = 0usize;
+    for (number, line) in reader.lines().enumerate() {
+        let Some(op) = parse_line(&line?).map_err(|msg| invalid(number, msg))? else {
+            continue;
+        };
+        match op {
+            Op::Push(_) => len += 1,
+            Op::Pop => len = len.saturating_sub(1),
+            Op::Get(index) | Op::Set(index, _) if index >= len => {
+                return Err(invalid(
+                    number,
+                    format!("index {index} out of bounds for length {len}"),
+                ));
+            }
+            Op::Get(_) | Op::Set(_, _) => (),
+            Op::Truncate(new_len) => len = len.min(new_len),
+            Op::Clear => len = 0,
+        }
+        ops.push(op);
+    }
+    Ok(ops)
+}
+
+/// Records the operations an application performs on one of its arrays, by
+/// calling the method of the same name alongside each operation.
+///
+/// ```no_run
+/// use array_bench::trace::Recorder;
+/// use std::fs::File;
+/// use std::io::BufWriter;
+///
+/// let mut recorder = Recorder::new(BufWriter::new(File::create("app.trace")?));
+/// let mut values = vec![];
+/// values.push(42);
+/// recorder.push(42);
+/// recorder.finish()?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+///
+/// Recording stops at the first write error, which is returned by
+/// [`Recorder::finish`].
+pub struct Recorder<W: Write> {
+    writer: W,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> Recorder<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            error: None,
+        }
+    }
+
+    /// Record an operation.
+    pub fn record(&mut self, op: Op) {
+        if self.error.is_none() {
+            if let Err(err) = writeln!(self.writer, "{op}") {
+                self.error = Some(err);
+            }
+        }
+    }
+
+    pub fn push(&mut self, value: usize) {
+        self.record(Op::Push(value));
+    }
+
+    pub fn pop(&mut self) {
+        self.record(Op::Pop);
+    }
+
+    pub fn get(&mut self, index: usize) {
+        self.record(Op::Get(index));
+    }
+
+    pub fn set(&mut self, index: usize, value: usize) {
+        self.record(Op::Set(index, value));
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        self.record(Op::Truncate(len));
+    }
+
+    pub fn clear(&mut self) {
+        self.record(Op::Clear);
+    }
+
+    /// Flush the recorded operations, returning the writer, or the first
+    /// error that occurred while recording.
+    pub fn finish(mut self) -> io::Result<W> {
+        if let Some(err) = self.error.take() {
+            return Err(err);
+        }
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(text: &str) -> String {
+        let err = read(text.as_bytes()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        err.to_string()
+    }
+
+    #[test]
+    fn test_read() {
+        let text = "# comment\n\npush 1\n  push 2  \nget 1\nset 0 7\npop\ntruncate 5\nclear\n";
+        let ops = read(text.as_bytes()).unwrap();
+        assert_eq!(
+            ops,
+            [
+                Op::Push(1),
+                Op::Push(2),
+                Op::Get(1),
+                Op::Set(0, 7),
+                Op::Pop,
+                Op::Truncate(5),
+                Op::Clear
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_errors() {
+        assert_eq!(error("push 1\nfrob 2\n"), "line 2: unknown operation: frob");
+        assert_eq!(
+            error("push\n"),
+            "line 1: wrong number of arguments for push"
+        );
+        assert_eq!(
+            error("pop 1\n"),
+            "line 1: wrong number of arguments for pop"
+        );
+        assert_eq!(
+            error("set 0\n"),
+            "line 1: wrong number of arguments for set"
+        );
+        assert_eq!(error("push -1\n"), "line 1: invalid number: -1");
+        assert_eq!(error("push 1\nget x\n"), "line 2: invalid number: x");
+    }
+
+    #[test]
+    fn test_read_out_of_bounds() {
+        assert_eq!(
+            error("get 0\n"),
+            "line 1: index 0 out of bounds for length 0"
+        );
+        assert_eq!(
+            error("push 1\npush 2\npop\nset 1 5\n"),
+            "line 4: index 1 out of bounds for length 1"
+        );
+        assert_eq!(
+            error("push 1\npush 2\ntruncate 1\nget 1\n"),
+            "line 4: index 1 out of bounds for length 1"
+        );
+        assert_eq!(
+            error("push 1\nclear\nget 0\n"),
+            "line 3: index 0 out of bounds for length 0"
+        );
+        // popping an empty array is allowed and leaves it empty
+        assert!(read("pop\npush 1\nget 0\n".as_bytes()).is_ok());
+    }
+
+    #[test]
+    fn test_recorder() {
+        let mut recorder = Recorder::new(vec![]);
+        recorder.push(3);
+        recorder.set(0, 4);
+        recorder.get(0);
+        recorder.truncate(0);
+        recorder.clear();
+        recorder.pop();
+        let written = recorder.finish().unwrap();
+        let ops = read(written.as_slice()).unwrap();
+        assert_eq!(
+            ops,
+            [
+                Op::Push(3),
+                Op::Set(0, 4),
+                Op::Get(0),
+                Op::Truncate(0),
+                Op::Clear,
+                Op::Pop
+            ]
+        );
+    }
+}
@@ -0,0 +1,217 @@
+//
+// Copyright (c) 2025 Nathan Fiedler
+//
+use crate::Config;
+use crate::allocator::{self, Counts};
+use crate::arrays::ResizableArray;
+use crate::element::Element;
+use crate::histogram::Histogram;
+use crate::rng::Rng;
+use std::hint::black_box;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Shape of the sequence of pushes and pops performed by a mixed workload.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Pattern {
+    /// Every operation is a push with the configured probability, otherwise
+    /// a pop.
+    #[default]
+    RandomWalk,
+    /// Push `period` values, then pop them all, repeatedly.
+    Sawtooth,
+    /// Runs of up to `period` operations of the same kind, each run being
+    /// pushes with the configured probability, otherwise pops.
+    Bursty,
+}
+
+impl Pattern {
+    /// Name used to select the pattern and to identify it in results.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Pattern::RandomWalk => "random-walk",
+            Pattern::Sawtooth => "sawtooth",
+            Pattern::Bursty => "bursty",
+        }
+    }
+}
+
+impl FromStr for Pattern {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "random-walk" => Ok(Pattern::RandomWalk),
+            "sawtooth" => Ok(Pattern::Sawtooth),
+            "bursty" => Ok(Pattern::Bursty),
+            _ => Err(format!(
+                "unknown workload: {s} (expected random-walk, sawtooth, or bursty)"
+            )),
+        }
+    }
+}
+
+/// Settings that determine the sequence of operations of a mixed workload.
+#[derive(Clone, Copy, Debug)]
+pub struct Workload {
+    pub pattern: Pattern,
+    /// Probability that an operation (or a burst) is a push, from 0 to 1.
+    pub push_ratio: f64,
+    /// Length of each tooth of the sawtooth, or the longest burst.
+    pub period: usize,
+}
+
+impl Default for Workload {
+    fn default() -> Self {
+        Self {
+            pattern: Pattern::RandomWalk,
+            push_ratio: 0.5,
+            period: 1_000,
+        }
+    }
+}
+
+impl Workload {
+    /// Name of the pattern and its settings, such as `random-walk (p=0.5)`.
+    pub fn label(&self) -> String {
+        match self.pattern {
+            Pattern::RandomWalk => format!("{} (p={})", self.pattern.name(), self.push_ratio),
+            Pattern::Sawtooth => format!("{} (period={})", self.pattern.name(), self.period),
+            Pattern::Bursty => format!(
+                "{} (p={}, period={})",
+                self.pattern.name(),
+                self.push_ratio,
+                self.period
+            ),
+        }
+    }
+}
+
+/// A single operation of a mixed workload.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Step {
+    Push,
+    Pop,
+}
+
+/// Returns `count` operations following the pattern of the workload, using
+/// the pseudo-random sequence determined by `seed`. A pop is never generated
+/// for an empty array; a push is generated in its place.
+pub fn generate(workload: &Workload, count: usize, seed: u64) -> Vec<Step> {
+    let mut rng = Rng::new(seed);
+    // compare against a 53-bit value so that a ratio of 1 always pushes
+    let chance =
+        |rng: &mut Rng| ((rng.next_u64() >> 11) as f64) < workload.push_ratio * (1u64 << 53) as f64;
+    let mut steps = Vec::with_capacity(count);
+    let mut len = 0usize;
+    let mut step = |steps: &mut Vec<Step>, push: bool| {
+        if push || len == 0 {
+            steps.push(Step::Push);
+            len += 1;
+        } else {
+            steps.push(Step::Pop);
+            len -= 1;
+        }
+    };
+    while steps.len() < count {
+        match workload.pattern {
+            Pattern::RandomWalk => {
+                let push = chance(&mut rng);
+                step(&mut steps, push);
+            }
+            Pattern::Sawtooth => {
+                let push = (steps.len() / workload.period) % 2 == 0;
+                step(&mut steps, push);
+            }
+            Pattern::Bursty => {
+                let push = chance(&mut rng);
+                let burst = 1 + rng.below(workload.period);
+                for _ in 0..burst.min(count - steps.len()) {
+                    step(&mut steps, push);
+                }
+            }
+        }
+    }
+    steps
+}
+
+/// Results of running a mixed workload several times.
+pub struct Churn {
+    /// Number of operations in each run.
+    pub ops: usize,
+    /// Time taken by each run, without timing the batches.
+    pub elapsed: Vec<Duration>,
+    /// Time taken by each batch of operations, in nanoseconds, recorded in
+    /// separate runs.
+    pub histogram: Histogram,
+    /// Allocator activity, averaged over the runs.
+    pub allocs: Counts,
+    /// Largest number of elements held by the array at any time.
+    pub max_len: usize,
+}
+
+/// Perform the configured workload of `config.size` operations on a new
+/// array `config.runs` times, timing each run as a whole. Then perform it
+/// on a new array another `config.runs` times, timing each batch of
+/// `config.batch` operations instead, so that reading the timer does not
+/// slow down the runs that measure throughput.
+pub fn measure<E: Element, A: ResizableArray<E>>(new: fn() -> A, config: &Config) -> Churn {
+    let steps = generate(&config.workload, config.size, config.seed);
+    let mut elapsed = vec![];
+    let mut allocs = Counts::default();
+    for _ in 0..config.runs {
+        let mut coll = new();
+        let before = allocator::counts();
+        let start = config.timer.start();
+        perform::<E, A>(&mut coll, &steps, &mut 0);
+        elapsed.push(start.elapsed());
+        allocs = allocs + (allocator::counts() - before);
+    }
+    let mut histogram = Histogram::new();
+    for _ in 0..config.runs {
+        let mut coll = new();
+        let mut value = 0;
+        for batch in steps.chunks(config.batch) {
+            let start = config.timer.start();
+            perform::<E, A>(&mut coll, batch, &mut value);
+            histogram.record(start.elapsed().as_nanos() as u64);
+        }
+    }
+    Churn {
+        ops: steps.len(),
+        elapsed,
+        histogram,
+        allocs: allocs / config.runs as u64,
+        max_len: max_len(&steps),
+    }
+}
+
+/// Perform the steps on the array, pushing values numbered from `value`
+/// onwards.
+fn perform<E: Element, A: ResizableArray<E>>(coll: &mut A, steps: &[Step], value: &mut usize) {
+    for step in steps {
+        match step {
+            Step::Push => {
+                coll.push(E::new(*value));
+                *value += 1;
+            }
+            Step::Pop => {
+                black_box(coll.pop());
+            }
+        }
+    }
+}
+
+/// Largest number of elements held at any time while performing the steps.
+fn max_len(steps: &[Step]) -> usize {
+    let mut len = 0usize;
+    let mut max = 0;
+    for step in steps {
+        match step {
+            Step::Push => len += 1,
+            Step::Pop => len -= 1,
+        }
+        max = max.max(len);
+    }
+    max
+}